@@ -0,0 +1,58 @@
+//! Compare the frontiers of the best first searches on both depths of the example burrow.
+//!
+//! Run with `cargo bench`. Each search is run a few times and the fastest run is reported.
+
+use std::cmp::Reverse;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use aoc2021::amphipod::frontier::BucketQueue;
+use aoc2021::amphipod::search::{BestFirst, Queued, SearchStrategy};
+use aoc2021::amphipod::Map;
+use aoc2021::days::day23;
+use priority_queue::PriorityQueue;
+
+/// The number of times each search is run.
+const RUNS: usize = 5;
+
+type Buckets = BestFirst<BucketQueue<Queued>>;
+type Heap = BestFirst<PriorityQueue<Queued, Reverse<i32>>>;
+
+/// The fastest of a few searches, and the number of maps expanded.
+fn bench(strategy: &dyn SearchStrategy, map: &Map) -> (Duration, usize) {
+	(0..RUNS)
+		.map(|_| {
+			let start = Instant::now();
+			let search = black_box(strategy.search(black_box(map)));
+
+			(start.elapsed(), search.stats.expanded)
+		})
+		.min()
+		.expect("no runs")
+}
+
+fn main() {
+	let map: Map = include_str!("../examples/example23.txt")
+		.parse()
+		.expect("the example should be a burrow");
+	let unfolded = day23::unfold(&map).expect("the example should unfold");
+	let maps = [(2, map), (4, unfolded)];
+
+	let strategies: [(&str, &str, Box<dyn SearchStrategy>); 4] = [
+		("dijkstra", "buckets", Box::new(Buckets::dijkstra())),
+		("dijkstra", "heap", Box::new(Heap::dijkstra())),
+		("astar", "buckets", Box::new(Buckets::a_star())),
+		("astar", "heap", Box::new(Heap::a_star())),
+	];
+
+	for (depth, map) in &maps {
+		for (search, frontier, strategy) in &strategies {
+			let (elapsed, expanded) = bench(strategy.as_ref(), map);
+
+			println!(
+				"depth {}  {:<8}  {:<7}  {:>10.3?}  {:>6} maps expanded",
+				depth, search, frontier, elapsed, expanded
+			);
+		}
+	}
+}
@@ -0,0 +1,469 @@
+//! The layout of a burrow: where the walls, the hallway and the rooms are.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter;
+use std::str::FromStr;
+
+use super::Position;
+
+/// The top of the burrow: the wall and the hallway.
+const HALLWAY: [&str; 2] = ["#############", "#...........#"];
+/// The first row of rooms, right below the hallway.
+const TOP_ROOM: &str = "###.#.#.#.###";
+/// Every room row below the first one.
+const ROOM: &str = "  #.#.#.#.#";
+/// The wall at the bottom of the rooms.
+const BOTTOM: &str = "  #########";
+
+/// Render the empty burrow from the puzzle with rooms that are `depth` squares deep.
+pub fn cave(depth: u8) -> String {
+	let rooms = (0..depth).map(|y| match y {
+		0 => TOP_ROOM,
+		_ => ROOM,
+	});
+
+	HALLWAY
+		.into_iter()
+		.chain(rooms)
+		.chain(iter::once(BOTTOM))
+		.collect::<Vec<&str>>()
+		.join("\n")
+}
+
+/// The energy each kind of amphipod needs for a single step.
+///
+/// The default table is the one from the puzzle: `A=1 B=10 C=100 D=1000`. Other tables are
+/// written the same way, as `kind=energy` pairs separated by whitespace.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct EnergyCosts {
+	costs: BTreeMap<char, i32>,
+}
+
+impl EnergyCosts {
+	/// A table without any kinds of amphipods.
+	pub fn empty() -> Self {
+		EnergyCosts { costs: BTreeMap::new() }
+	}
+
+	/// The energy a step costs for amphipods of kind `color`.
+	pub fn get(&self, color: char) -> Option<i32> {
+		self.costs.get(&color).copied()
+	}
+
+	/// Set the energy a step costs for amphipods of kind `color`.
+	pub fn insert(&mut self, color: char, energy: i32) {
+		self.costs.insert(color, energy);
+	}
+
+	/// Every kind of amphipod in the table and the energy it needs per step.
+	pub fn iter(&self) -> impl Iterator<Item = (char, i32)> + '_ {
+		self.costs.iter().map(|(color, energy)| (*color, *energy))
+	}
+}
+
+impl Default for EnergyCosts {
+	fn default() -> Self {
+		EnergyCosts {
+			costs: [('A', 1), ('B', 10), ('C', 100), ('D', 1000)].into_iter().collect(),
+		}
+	}
+}
+
+/// A pair in an energy cost table that could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergyCostError(pub String);
+
+impl fmt::Display for EnergyCostError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid energy cost {:?}, expected kind=energy", self.0)
+	}
+}
+
+impl std::error::Error for EnergyCostError {}
+
+impl FromStr for EnergyCosts {
+	type Err = EnergyCostError;
+
+	/// Parse `kind=energy` pairs, like `A=1 B=10`. Only the listed kinds are in the table.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut costs = EnergyCosts::empty();
+
+		for pair in s.split_whitespace() {
+			let error = || EnergyCostError(pair.to_string());
+
+			let (color, energy) = pair.split_once('=').ok_or_else(error)?;
+			let color = match color.chars().collect::<Vec<char>>()[..] {
+				[c] if c.is_ascii_uppercase() => c,
+				_ => return Err(error()),
+			};
+			let energy = energy.parse::<i32>().ok().filter(|e| *e >= 0).ok_or_else(error)?;
+
+			costs.insert(color, energy);
+		}
+
+		Ok(costs)
+	}
+}
+
+/// A room below the hallway, home to a single kind of amphipod.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Room {
+	/// The kind of amphipod that belongs in the room.
+	pub color: char,
+	/// The column of the room.
+	pub x: u8,
+	/// The squares of the room, from the top down.
+	pub squares: Vec<Position>,
+}
+
+impl Room {
+	/// Number of squares in the room.
+	pub fn depth(&self) -> usize {
+		self.squares.len()
+	}
+
+	/// Whether `position` is one of the squares of the room.
+	pub fn contains(&self, position: &Position) -> bool {
+		self.squares.contains(position)
+	}
+}
+
+/// The fixed layout of a burrow, shared by every state of the amphipods in it.
+///
+/// The hallway is the top row of open squares. A room is a column of open squares going straight
+/// down from the hallway. The squares in the hallway right outside a room are entrances where no
+/// amphipod may stop. Everything else is wall.
+///
+/// Rooms are given to the kinds of amphipods in the drawing in alphabetical order, from left to
+/// right. If the drawing doesn't have a kind of amphipod for every room, the rooms are given to
+/// `'A'`, `'B'` and so on instead.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Burrow {
+	/// The drawing of the empty burrow, one row of characters per line.
+	pub drawing: Vec<Vec<char>>,
+	/// The row of the hallway.
+	pub hallway_y: u8,
+	/// Every square in the hallway, from left to right.
+	pub hallway: Vec<Position>,
+	/// The hallway squares where an amphipod may stop, every square except the entrances.
+	pub stops: Vec<Position>,
+	/// The rooms, from left to right.
+	pub rooms: Vec<Room>,
+	/// Every square an amphipod can be in, the hallway first and then the rooms from left to
+	/// right. A packed [`State`](super::State) has a few bits for each of them.
+	pub cells: Vec<Position>,
+	/// The energy each kind of amphipod needs for a single step.
+	pub costs: EnergyCosts,
+}
+
+impl Burrow {
+	/// Find the burrow in a drawing, with the default energy costs.
+	///
+	/// Amphipods in the drawing are treated as open squares.
+	pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Self {
+		Burrow::with_costs(lines, EnergyCosts::default())
+	}
+
+	/// Find the burrow in a drawing, with the given energy costs.
+	pub fn with_costs<S: AsRef<str>>(lines: &[S], costs: EnergyCosts) -> Self {
+		let mut kinds: Vec<char> = lines
+			.iter()
+			.flat_map(|line| line.as_ref().chars())
+			.filter(char::is_ascii_alphabetic)
+			.collect();
+		kinds.sort_unstable();
+		kinds.dedup();
+
+		let drawing: Vec<Vec<char>> = lines
+			.iter()
+			.map(|line| {
+				line.as_ref()
+					.chars()
+					.map(|c| match c.is_ascii_alphabetic() {
+						true => '.',
+						false => c,
+					})
+					.collect()
+			})
+			.collect();
+
+		let is_open = |x: usize, y: usize| drawing.get(y).and_then(|row| row.get(x)) == Some(&'.');
+
+		let hallway_y = drawing.iter().position(|row| row.contains(&'.')).unwrap_or(0);
+
+		let hallway: Vec<Position> = (0..drawing.get(hallway_y).map_or(0, Vec::len))
+			.filter(|x| is_open(*x, hallway_y))
+			.map(|x| Position {
+				x: x as u8,
+				y: hallway_y as u8,
+			})
+			.collect();
+
+		let room_columns: Vec<u8> = hallway
+			.iter()
+			.filter(|square| is_open(square.x as usize, hallway_y + 1))
+			.map(|square| square.x)
+			.collect();
+
+		if kinds.len() != room_columns.len() {
+			kinds = ('A'..).take(room_columns.len()).collect();
+		}
+
+		let rooms: Vec<Room> = room_columns
+			.into_iter()
+			.zip(kinds)
+			.map(|(x, color)| Room {
+				color,
+				x,
+				squares: ((hallway_y + 1)..)
+					.take_while(|y| is_open(x as usize, *y))
+					.map(|y| Position { x, y: y as u8 })
+					.collect(),
+			})
+			.collect();
+
+		let stops = hallway
+			.iter()
+			.filter(|square| !rooms.iter().any(|room| room.x == square.x))
+			.copied()
+			.collect();
+
+		let cells = hallway
+			.iter()
+			.chain(rooms.iter().flat_map(|room| &room.squares))
+			.copied()
+			.collect();
+
+		Burrow {
+			drawing,
+			hallway_y: hallway_y as u8,
+			hallway,
+			stops,
+			rooms,
+			cells,
+			costs,
+		}
+	}
+
+	/// The burrow from the puzzle with rooms that are `depth` squares deep.
+	pub fn standard(depth: u8) -> Self {
+		let drawing = cave(depth);
+		let lines: Vec<&str> = drawing.lines().collect();
+
+		Burrow::from_lines(&lines)
+	}
+
+	/// The hallway squares right outside a room, where no amphipod may stop.
+	pub fn entrances(&self) -> Vec<Position> {
+		self.rooms
+			.iter()
+			.map(|room| Position {
+				x: room.x,
+				y: self.hallway_y,
+			})
+			.collect()
+	}
+
+	/// The room that is home to amphipods of kind `color`.
+	pub fn room(&self, color: char) -> Option<&Room> {
+		self.rooms.iter().find(|room| room.color == color)
+	}
+
+	/// The energy a step costs for amphipods of kind `color`.
+	pub fn energy_cost(&self, color: char) -> Option<i32> {
+		self.costs.get(color)
+	}
+
+	/// More energy than any plan in the burrow can need, or `None` if that doesn't fit in an `i32`.
+	///
+	/// An amphipod moves at most twice, and never walks over a square twice in a move.
+	pub fn max_energy(&self) -> Option<i32> {
+		let steps = i32::try_from(2 * self.cells.len()).ok()?;
+
+		self.rooms.iter().try_fold(0_i32, |total, room| {
+			let cost = self.energy_cost(room.color).unwrap_or(0);
+			let amphipods = i32::try_from(room.depth()).ok()?;
+			total.checked_add(cost.checked_mul(amphipods)?.checked_mul(steps)?)
+		})
+	}
+
+	/// The room that `position` is in, if any.
+	pub fn room_at(&self, position: &Position) -> Option<&Room> {
+		self.rooms.iter().find(|room| room.contains(position))
+	}
+
+	/// The number of `position` in [`Burrow::cells`], if it is a square an amphipod can be in.
+	pub fn cell(&self, position: &Position) -> Option<usize> {
+		self.cells.iter().position(|cell| cell == position)
+	}
+
+	/// Whether `position` is in the hallway.
+	pub fn in_hallway(&self, position: &Position) -> bool {
+		position.y == self.hallway_y
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn burrow(lines: &[&str]) -> Burrow {
+		Burrow::from_lines(lines)
+	}
+
+	#[test]
+	fn test_standard_burrow_should_stop_outside_the_rooms() {
+		// Given
+		let burrow = Burrow::standard(2);
+
+		// When
+		let result: Vec<u8> = burrow.stops.iter().map(|p| p.x).collect();
+
+		// Then
+		assert_eq!(vec![1, 2, 4, 6, 8, 10, 11], result);
+	}
+
+	#[test]
+	fn test_standard_burrow_should_have_four_rooms_of_the_given_depth() {
+		// Given
+		let burrow = Burrow::standard(4);
+
+		// When
+		let result: Vec<(u8, usize)> = burrow.rooms.iter().map(|room| (room.x, room.depth())).collect();
+
+		// Then
+		assert_eq!(vec![(3, 4), (5, 4), (7, 4), (9, 4)], result);
+	}
+
+	#[test]
+	fn test_longer_hallway_with_uneven_rooms_should_be_found() {
+		// Given
+		let burrow = burrow(&[
+			"################",
+			"#..............#",
+			"###.##.#.####.##",
+			"  #.##.#.#  #.#",
+			"  ####.###  ###",
+			"     ###",
+		]);
+
+		// When
+		let rooms: Vec<(u8, usize)> = burrow.rooms.iter().map(|room| (room.x, room.depth())).collect();
+		let stops: Vec<u8> = burrow.stops.iter().map(|p| p.x).collect();
+
+		// Then
+		assert_eq!(vec![(3, 2), (6, 3), (8, 2), (13, 2)], rooms);
+		assert_eq!(vec![1, 2, 4, 5, 7, 9, 10, 11, 12, 14], stops);
+	}
+
+	#[test]
+	fn test_amphipods_in_the_drawing_should_be_open_squares() {
+		// Given
+		let burrow = burrow(&["#######", "#.A...#", "###B###", "  ###"]);
+
+		// When
+		let result = (burrow.hallway.len(), burrow.rooms[0].squares.clone());
+
+		// Then
+		assert_eq!((5, vec![Position { x: 3, y: 2 }]), result);
+	}
+
+	#[test]
+	fn test_room_should_be_looked_up_by_kind() {
+		// Given
+		let burrow = Burrow::standard(2);
+
+		// When
+		let result = burrow.room('C').map(|room| room.x);
+
+		// Then
+		assert_eq!(Some(7), result);
+	}
+
+	#[test]
+	fn test_rooms_should_be_given_to_the_kinds_in_the_drawing() {
+		// Given
+		let burrow = burrow(&["#########", "#.......#", "###Y#W#X###", "  #########"]);
+
+		// When
+		let result: Vec<char> = burrow.rooms.iter().map(|room| room.color).collect();
+
+		// Then
+		assert_eq!(vec!['W', 'X', 'Y'], result);
+	}
+
+	#[test]
+	fn test_default_costs_should_be_from_the_puzzle() {
+		// Given
+		let costs = EnergyCosts::default();
+
+		// When
+		let result: Vec<(char, i32)> = costs.iter().collect();
+
+		// Then
+		assert_eq!(vec![('A', 1), ('B', 10), ('C', 100), ('D', 1000)], result);
+	}
+
+	#[test]
+	fn test_costs_should_be_parsed_from_pairs() {
+		// Given
+		let input = "E=10000 F=5\nA=2";
+
+		// When
+		let result = input.parse::<EnergyCosts>().unwrap();
+
+		// Then
+		assert_eq!(vec![('A', 2), ('E', 10000), ('F', 5)], result.iter().collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_invalid_cost_should_be_an_error() {
+		// Given
+		let input = "A=1 B:10";
+
+		// When
+		let result = input.parse::<EnergyCosts>();
+
+		// Then
+		assert_eq!(Err(EnergyCostError(String::from("B:10"))), result);
+	}
+
+	#[test]
+	fn test_room_for_kind_without_room_should_be_none() {
+		// Given
+		let burrow = Burrow::standard(2);
+
+		// When
+		let result = burrow.room('E');
+
+		// Then
+		assert_eq!(None, result);
+	}
+
+	#[test]
+	fn test_max_energy_should_walk_every_square_twice() {
+		// Given
+		let burrow = Burrow::standard(2);
+
+		// When
+		let result = burrow.max_energy();
+
+		// Then
+		assert_eq!(19, burrow.cells.len());
+		assert_eq!(Some((1 + 10 + 100 + 1000) * 2 * 2 * 19), result);
+	}
+
+	#[test]
+	fn test_max_energy_that_does_not_fit_should_be_none() {
+		// Given
+		let mut burrow = Burrow::standard(2);
+		burrow.costs.insert('D', i32::MAX / 2);
+
+		// When
+		let result = burrow.max_energy();
+
+		// Then
+		assert_eq!(None, result);
+	}
+}
@@ -0,0 +1,18 @@
+//! Maps shared by the tests of the amphipod solver.
+
+use super::Map;
+
+/// The example burrow with the amphipods of the first two rooms swapped, which takes 46 energy to
+/// organize.
+pub fn swapped_rooms() -> Map {
+	"#############\n#...........#\n###B#A#C#D###\n  #A#B#C#D#\n  #########\n"
+		.parse()
+		.unwrap()
+}
+
+/// The example burrow from the puzzle, which takes 12521 energy to organize.
+pub fn example() -> Map {
+	"#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n"
+		.parse()
+		.unwrap()
+}
@@ -0,0 +1,173 @@
+//! Queues of maps waiting to be expanded by a best first search.
+//!
+//! Many maps wait with the same energy, so a [`BucketQueue`] with a list of maps for each energy
+//! only compares energies, never maps. The general [`PriorityQueue`] is kept around as a
+//! [`Frontier`] to compare with.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+use priority_queue::PriorityQueue;
+
+/// Items waiting to be taken out, lowest priority first.
+pub trait Frontier<T>: Default {
+	/// Add an item with the given priority.
+	fn push(&mut self, item: T, priority: i32);
+
+	/// Take out an item with the lowest priority, together with the priority.
+	fn pop(&mut self) -> Option<(T, i32)>;
+
+	/// The number of items waiting.
+	fn len(&self) -> usize;
+
+	/// Whether there are no items waiting.
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+/// A priority queue with a bucket of items for each priority that has any items waiting.
+///
+/// The buckets are kept by priority in a map rather than in a list indexed by priority, so the
+/// memory used grows with the number of items waiting and not with the highest priority, which
+/// can get large when a burrow has high energy costs.
+///
+/// Items with the same priority are popped last pushed first, which makes A* go deep and find an
+/// organized map sooner.
+#[derive(Clone, Debug)]
+pub struct BucketQueue<T> {
+	buckets: BTreeMap<i32, Vec<T>>,
+	len: usize,
+}
+
+impl<T> Default for BucketQueue<T> {
+	fn default() -> Self {
+		BucketQueue {
+			buckets: BTreeMap::new(),
+			len: 0,
+		}
+	}
+}
+
+impl<T> Frontier<T> for BucketQueue<T> {
+	fn push(&mut self, item: T, priority: i32) {
+		self.buckets.entry(priority).or_default().push(item);
+		self.len += 1;
+	}
+
+	fn pop(&mut self) -> Option<(T, i32)> {
+		let mut lowest = self.buckets.first_entry()?;
+		let item = lowest.get_mut().pop().expect("only buckets with items are kept");
+		let priority = *lowest.key();
+		if lowest.get().is_empty() {
+			lowest.remove();
+		}
+		self.len -= 1;
+
+		Some((item, priority))
+	}
+
+	fn len(&self) -> usize {
+		self.len
+	}
+}
+
+impl<T: Hash + Eq> Frontier<T> for PriorityQueue<T, Reverse<i32>> {
+	/// Pushing an item that is already waiting changes its priority instead.
+	fn push(&mut self, item: T, priority: i32) {
+		PriorityQueue::push(self, item, Reverse(priority));
+	}
+
+	fn pop(&mut self) -> Option<(T, i32)> {
+		PriorityQueue::pop(self).map(|(item, Reverse(priority))| (item, priority))
+	}
+
+	fn len(&self) -> usize {
+		PriorityQueue::len(self)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Push the items with their priorities, then pop all of them.
+	fn drain<F: Frontier<char>>(items: &[(char, i32)]) -> Vec<(char, i32)> {
+		let mut frontier = F::default();
+		items.iter().for_each(|(item, priority)| frontier.push(*item, *priority));
+
+		std::iter::from_fn(|| frontier.pop()).collect()
+	}
+
+	#[test]
+	fn test_bucket_queue_should_pop_lowest_priority_first() {
+		// Given
+		let items = [('a', 30), ('b', 10), ('c', 20), ('d', 10)];
+
+		// When
+		let result = drain::<BucketQueue<char>>(&items);
+
+		// Then
+		assert_eq!(vec![('d', 10), ('b', 10), ('c', 20), ('a', 30)], result);
+	}
+
+	#[test]
+	fn test_bucket_queue_should_allow_lower_priority_after_pop() {
+		// Given
+		let mut frontier = BucketQueue::default();
+		frontier.push('a', 5);
+		frontier.push('b', 7);
+		frontier.pop();
+
+		// When
+		frontier.push('c', 2);
+
+		// Then
+		assert_eq!((Some(('c', 2)), Some(('b', 7)), None), (frontier.pop(), frontier.pop(), frontier.pop()));
+	}
+
+	#[test]
+	fn test_bucket_queue_should_count_its_items() {
+		// Given
+		let mut frontier = BucketQueue::default();
+
+		// When
+		frontier.push('a', 3);
+		frontier.push('b', 3);
+		frontier.pop();
+
+		// Then
+		assert_eq!((1, false), (frontier.len(), frontier.is_empty()));
+	}
+
+	#[test]
+	fn test_bucket_queue_should_only_keep_buckets_with_items() {
+		// Given
+		let mut frontier = BucketQueue::default();
+
+		// When
+		frontier.push('a', 0);
+		frontier.push('b', 1 << 30);
+		frontier.push('c', 1 << 30);
+		frontier.pop();
+
+		// Then
+		assert_eq!(1, frontier.buckets.len());
+		assert_eq!((Some(('c', 1 << 30)), Some(('b', 1 << 30))), (frontier.pop(), frontier.pop()));
+	}
+
+	#[test]
+	fn test_frontiers_should_pop_the_same_priorities() {
+		// Given
+		let items: Vec<(char, i32)> = ('a'..='z').zip((0..).map(|n: i32| (n * 7919) % 23)).collect();
+
+		// When
+		let buckets = drain::<BucketQueue<char>>(&items);
+		let heap = drain::<PriorityQueue<char, Reverse<i32>>>(&items);
+
+		// Then
+		let priorities = |popped: &[(char, i32)]| popped.iter().map(|(_, priority)| *priority).collect::<Vec<_>>();
+		assert_eq!(priorities(&heap), priorities(&buckets));
+	}
+}
@@ -0,0 +1,282 @@
+//! Random puzzles, for trying the solver on more burrows than the one from the puzzle input.
+//!
+//! The amphipods are shuffled by a small generator of our own, so the same seed gives the same
+//! puzzles everywhere. Every puzzle is solved before it's handed out, which both makes sure it
+//! can be solved and tells how hard it is.
+
+use std::error;
+use std::fmt;
+
+use super::search::SearchStrategy;
+use super::{AStar, Map, MapError};
+
+/// The number of times the amphipods are shuffled before giving up on finding a puzzle.
+pub const ATTEMPTS: usize = 100;
+
+/// Reasons a puzzle could not be generated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerateError {
+	/// There are no kinds of amphipods, or the rooms are no squares deep.
+	NoAmphipods,
+	/// The burrow can't be drawn, or is too large.
+	Map(MapError),
+	/// None of the shuffles needed any moves, or could be organized at all.
+	NoPuzzle,
+}
+
+impl fmt::Display for GenerateError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			GenerateError::NoAmphipods => write!(f, "there must be at least one kind of amphipod and rooms at least one square deep"),
+			GenerateError::Map(error) => write!(f, "{}", error),
+			GenerateError::NoPuzzle => write!(f, "none of {} shuffles of the amphipods was a puzzle", ATTEMPTS),
+		}
+	}
+}
+
+impl error::Error for GenerateError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			GenerateError::Map(error) => Some(error),
+			GenerateError::NoAmphipods | GenerateError::NoPuzzle => None,
+		}
+	}
+}
+
+impl From<MapError> for GenerateError {
+	fn from(error: MapError) -> Self {
+		GenerateError::Map(error)
+	}
+}
+
+/// A seedable generator of random numbers, the SplitMix64 generator.
+///
+/// It's not meant for anything but shuffling amphipods.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+	/// A generator that always gives the same numbers for the same seed.
+	pub fn new(seed: u64) -> Self {
+		Rng(seed)
+	}
+
+	/// The next random number.
+	pub fn next_u64(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// A random number below `n`, which must not be 0.
+	///
+	/// Small numbers are very slightly more likely than large ones, which doesn't matter here.
+	pub fn below(&mut self, n: usize) -> usize {
+		(self.next_u64() % n as u64) as usize
+	}
+}
+
+/// The drawing of a burrow with a room for each of the first `kinds` kinds of amphipods, `depth`
+/// squares deep, and the amphipods shuffled into the rooms.
+///
+/// The kinds after `D` have no energy cost in the puzzle, so they get the next powers of ten in a
+/// table after the drawing. Returns an error if there are no amphipods, or the energy costs get
+/// too high.
+pub fn shuffled(kinds: usize, depth: usize, rng: &mut Rng) -> Result<Map, GenerateError> {
+	if kinds == 0 || depth == 0 {
+		return Err(GenerateError::NoAmphipods);
+	}
+
+	let mut amphipods: Vec<char> = ('A'..)
+		.take(kinds)
+		.flat_map(|kind| std::iter::repeat_n(kind, depth))
+		.collect();
+	for i in (1..amphipods.len()).rev() {
+		amphipods.swap(i, rng.below(i + 1));
+	}
+
+	let mut lines = vec![
+		"#".repeat(2 * kinds + 5),
+		format!("#{}#", ".".repeat(2 * kinds + 3)),
+		format!("###{}##", ".#".repeat(kinds)),
+	];
+	lines.extend((1..depth).map(|_| format!("  #{}", ".#".repeat(kinds))));
+	lines.push(format!("  {}", "#".repeat(2 * kinds + 1)));
+
+	let mut drawing = lines.join("\n") + "\n";
+	for amphipod in amphipods {
+		drawing = drawing.replacen("#.#", &format!("#{}#", amphipod), 1);
+	}
+
+	let costs = ('A'..)
+		.zip(0..)
+		.take(kinds)
+		.skip(4)
+		.map(|(kind, i)| Some(format!("{}={}", kind, 10_i32.checked_pow(i)?)))
+		.collect::<Option<Vec<String>>>()
+		.ok_or(MapError::TooLarge)?;
+	if !costs.is_empty() {
+		drawing += &format!("\n{}\n", costs.join(" "));
+	}
+
+	Ok(drawing.parse()?)
+}
+
+/// A puzzle that can be solved, and how hard it is.
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+	pub map: Map,
+	/// The energy the cheapest plan needs.
+	pub energy: i32,
+	/// The number of maps [`AStar`] expanded to find the cheapest plan.
+	pub expanded: usize,
+}
+
+impl fmt::Display for Puzzle {
+	/// The puzzle as it's read by [`Map::from_str`](std::str::FromStr), with the energy costs of
+	/// any kinds after `D`.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(f, "{}", self.map)?;
+
+		let costs: Vec<String> = self
+			.map
+			.burrow
+			.rooms
+			.iter()
+			.filter(|room| room.color > 'D')
+			.map(|room| format!("{}={}", room.color, self.map.burrow.energy_cost(room.color).unwrap_or(0)))
+			.collect();
+		if !costs.is_empty() {
+			writeln!(f, "\n{}", costs.join(" "))?;
+		}
+
+		Ok(())
+	}
+}
+
+/// A random puzzle with `kinds` kinds of amphipods in rooms `depth` squares deep.
+///
+/// The amphipods are shuffled until they can be organized, but aren't already, at most
+/// [`ATTEMPTS`] times.
+pub fn generate(kinds: usize, depth: usize, rng: &mut Rng) -> Result<Puzzle, GenerateError> {
+	for _ in 0..ATTEMPTS {
+		let map = shuffled(kinds, depth, rng)?;
+		let search = AStar.search(&map);
+
+		if let Some(plan) = search.plan.filter(|plan| plan.energy > 0) {
+			return Ok(Puzzle {
+				map,
+				energy: plan.energy,
+				expanded: search.stats.expanded,
+			});
+		}
+	}
+
+	Err(GenerateError::NoPuzzle)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_same_seed_should_give_the_same_numbers() {
+		// Given
+		let (mut first, mut second) = (Rng::new(23), Rng::new(23));
+
+		// When
+		let result: Vec<(u64, u64)> = (0..10).map(|_| (first.next_u64(), second.next_u64())).collect();
+
+		// Then
+		assert!(result.iter().all(|(first, second)| first == second));
+		assert_ne!(Rng::new(23).next_u64(), Rng::new(24).next_u64());
+	}
+
+	#[test]
+	fn test_numbers_should_stay_below_the_bound() {
+		// Given
+		let mut rng = Rng::new(0);
+
+		// When
+		let result: Vec<usize> = (0..1000).map(|_| rng.below(7)).collect();
+
+		// Then
+		assert!(result.iter().all(|n| *n < 7));
+		assert!((0..7).all(|n| result.contains(&n)));
+	}
+
+	#[test]
+	fn test_shuffled_burrow_should_be_the_standard_one() {
+		// Given
+		let mut rng = Rng::new(1);
+
+		// When
+		let map = shuffled(4, 2, &mut rng).unwrap();
+
+		// Then
+		let drawing = map.to_string();
+		let empty: String = drawing.chars().map(|c| if c.is_ascii_uppercase() { '.' } else { c }).collect();
+		assert_eq!(crate::amphipod::cave(2) + "\n", empty);
+	}
+
+	#[test]
+	fn test_puzzle_should_be_solved_and_read_back() {
+		// Given
+		let mut rng = Rng::new(2021);
+
+		// When
+		let puzzle = generate(4, 2, &mut rng).unwrap();
+
+		// Then
+		let read: Map = puzzle.to_string().parse().unwrap();
+		assert_eq!(puzzle.map, read);
+		assert_eq!(Some(puzzle.energy), AStar.search(&read).plan.map(|plan| plan.energy));
+		assert!(puzzle.energy > 0 && puzzle.expanded > 0);
+	}
+
+	#[test]
+	fn test_extra_kinds_should_get_energy_costs() {
+		// Given
+		let mut rng = Rng::new(5);
+
+		// When
+		let puzzle = generate(5, 1, &mut rng).unwrap();
+
+		// Then
+		let read: Map = puzzle.to_string().parse().unwrap();
+		assert!(puzzle.to_string().ends_with("\n\nE=10000\n"), "{}", puzzle);
+		assert_eq!(Some(10000), read.burrow.energy_cost('E'));
+	}
+
+	#[test]
+	fn test_same_seed_should_give_the_same_puzzles() {
+		// Given
+		let (mut first, mut second) = (Rng::new(7), Rng::new(7));
+
+		// When
+		let result = (generate(3, 2, &mut first).unwrap(), generate(3, 2, &mut second).unwrap());
+
+		// Then
+		assert_eq!(result.0.to_string(), result.1.to_string());
+	}
+
+	#[test]
+	fn test_too_many_kinds_should_be_an_error() {
+		assert_eq!(Some(GenerateError::Map(MapError::TooMuchEnergy)), shuffled(8, 2, &mut Rng::new(1)).err());
+		assert_eq!(Some(GenerateError::Map(MapError::TooLarge)), shuffled(11, 2, &mut Rng::new(1)).err());
+	}
+
+	#[test]
+	fn test_no_kinds_or_depth_should_be_an_error() {
+		assert_eq!(Some(GenerateError::NoAmphipods), generate(4, 0, &mut Rng::new(1)).err());
+		assert_eq!(Some(GenerateError::NoAmphipods), generate(0, 2, &mut Rng::new(1)).err());
+	}
+
+	#[test]
+	fn test_single_kind_should_never_be_a_puzzle() {
+		assert_eq!(Some(GenerateError::NoPuzzle), generate(1, 2, &mut Rng::new(1)).err());
+	}
+}
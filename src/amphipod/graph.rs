@@ -0,0 +1,234 @@
+//! The maps explored by a search, written out for Graphviz.
+//!
+//! A [`Graph`] records the [`Event`]s of a best first search, up to a limit on the number of maps,
+//! and writes them in the DOT language. Every map is drawn together with the cost it was reached
+//! with (g) and its estimate (h), and every move between two maps with the move and its energy.
+//! The maps and moves of the cheapest plan are highlighted, and maps that were reached but never
+//! expanded are drawn dashed.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use super::search::Event;
+use super::{Map, Move, Plan, State};
+
+/// A map in the graph.
+#[derive(Clone, Debug)]
+struct Node {
+	state: State,
+	/// The cheapest cost the map was reached with.
+	cost: i32,
+	estimate: i32,
+	expanded: bool,
+}
+
+/// The maps reached by a search and the moves between them.
+#[derive(Clone, Debug)]
+pub struct Graph {
+	limit: usize,
+	nodes: Vec<Node>,
+	/// Where each map is in `nodes`.
+	index: HashMap<State, usize>,
+	/// The moves between the maps, by where they are in `nodes`.
+	edges: Vec<(usize, usize, Move)>,
+	/// Whether maps were left out because of the limit.
+	truncated: bool,
+}
+
+impl Graph {
+	/// A graph of at most `limit` maps.
+	pub fn new(limit: usize) -> Self {
+		Graph {
+			limit,
+			nodes: vec![],
+			index: HashMap::new(),
+			edges: vec![],
+			truncated: false,
+		}
+	}
+
+	/// The number of maps in the graph.
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Whether there are no maps in the graph.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+
+	/// Whether maps were left out because there were more than the limit.
+	pub fn is_truncated(&self) -> bool {
+		self.truncated
+	}
+
+	/// Record something that happened during the search.
+	///
+	/// Maps first reached once the graph is full are left out, together with the moves to them.
+	pub fn record(&mut self, event: Event) {
+		match event {
+			Event::Reached {
+				state,
+				from,
+				cost,
+				estimate,
+			} => {
+				let to = match self.index.get(&state) {
+					Some(&to) => {
+						self.nodes[to].cost = self.nodes[to].cost.min(cost);
+						to
+					}
+					None if self.nodes.len() < self.limit => {
+						self.index.insert(state.clone(), self.nodes.len());
+						self.nodes.push(Node {
+							state,
+							cost,
+							estimate,
+							expanded: false,
+						});
+						self.nodes.len() - 1
+					}
+					None => {
+						self.truncated = true;
+						return;
+					}
+				};
+
+				if let Some((from, step)) = from {
+					if let Some(&from) = self.index.get(&from) {
+						self.edges.push((from, to, step));
+					}
+				}
+			}
+			Event::Expanded { state } => {
+				if let Some(&node) = self.index.get(&state) {
+					self.nodes[node].expanded = true;
+				}
+			}
+		}
+	}
+
+	/// The graph in the DOT language, with the maps of the burrow `start` is in.
+	///
+	/// The maps and moves of `plan`, made from `start`, are highlighted.
+	pub fn to_dot(&self, start: &Map, plan: Option<&Plan>) -> String {
+		let path: Vec<State> = plan
+			.and_then(|plan| plan.replay(start))
+			.into_iter()
+			.flatten()
+			.map(|map| map.pack())
+			.collect();
+		let on_path: HashSet<&State> = path.iter().collect();
+		let steps: HashSet<(&State, &State)> = path.windows(2).map(|pair| (&pair[0], &pair[1])).collect();
+
+		let mut dot = String::new();
+		writeln!(dot, "digraph burrow {{").unwrap();
+		if self.truncated {
+			writeln!(dot, "\t// More maps were reached than the limit of {}.", self.limit).unwrap();
+		}
+		writeln!(dot, "\tnode [shape=box, fontname=\"monospace\"];").unwrap();
+
+		for (i, node) in self.nodes.iter().enumerate() {
+			let map = node.state.unpack(&start.burrow);
+			let mut label: String = map.to_string().lines().map(|line| line.to_string() + "\\l").collect();
+			label += &format!("g = {}, h = {}\\l", node.cost, node.estimate);
+
+			let mut attributes = vec![format!("label=\"{}\"", label)];
+			if !node.expanded {
+				attributes.push("style=dashed".to_string());
+			}
+			if on_path.contains(&node.state) {
+				attributes.push("color=red, penwidth=3".to_string());
+			}
+			writeln!(dot, "\tn{} [{}];", i, attributes.join(", ")).unwrap();
+		}
+
+		for (from, to, step) in &self.edges {
+			let mut attributes = vec![format!("label=\"{}\"", step)];
+			if steps.contains(&(&self.nodes[*from].state, &self.nodes[*to].state)) {
+				attributes.push("color=red, penwidth=3".to_string());
+			}
+			writeln!(dot, "\tn{} -> n{} [{}];", from, to, attributes.join(", ")).unwrap();
+		}
+
+		writeln!(dot, "}}").unwrap();
+
+		dot
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::fixtures::swapped_rooms;
+	use crate::amphipod::heuristic;
+	use crate::amphipod::search::BestFirst;
+
+	/// Search the map with A*, recording at most `limit` maps.
+	fn explore(map: &Map, limit: usize) -> (Graph, Option<Plan>) {
+		let mut graph = Graph::new(limit);
+		let plan = <BestFirst>::a_star().search_observed(map, &mut |_| {}, &mut |event| graph.record(event)).plan;
+
+		(graph, plan)
+	}
+
+	#[test]
+	fn test_graph_should_keep_to_the_limit() {
+		// Given
+		let map = swapped_rooms();
+
+		// When
+		let (graph, _) = explore(&map, 5);
+
+		// Then
+		assert_eq!((5, true), (graph.len(), graph.is_truncated()));
+		assert!(graph.edges.iter().all(|(from, to, _)| *from < 5 && *to < 5));
+	}
+
+	#[test]
+	fn test_every_move_of_the_plan_should_be_highlighted() {
+		// Given
+		let map = swapped_rooms();
+		let (graph, plan) = explore(&map, 10_000);
+		let plan = plan.unwrap();
+
+		// When
+		let dot = graph.to_dot(&map, Some(&plan));
+
+		// Then
+		let highlighted = dot.lines().filter(|line| line.contains("->") && line.contains("color=red")).count();
+		assert!(!graph.is_truncated());
+		assert_eq!(plan.moves.len(), highlighted);
+		assert!(dot.starts_with("digraph burrow {\n") && dot.ends_with("}\n"));
+	}
+
+	#[test]
+	fn test_start_should_be_labelled_with_its_costs() {
+		// Given
+		let map = swapped_rooms();
+		let (graph, _) = explore(&map, 1);
+
+		// When
+		let dot = graph.to_dot(&map, None);
+
+		// Then
+		let drawing = "#############\\l#...........#\\l###B#A#C#D###\\l  #A#B#C#D#\\l  #########\\l";
+		let label = format!("{}g = 0, h = {}\\l", drawing, heuristic(&map));
+		assert!(dot.contains(&format!("n0 [label=\"{}\"];", label)), "{}", dot);
+	}
+
+	#[test]
+	fn test_moves_should_be_labelled_with_their_energy() {
+		// Given
+		let map = swapped_rooms();
+		let (graph, _) = explore(&map, 100);
+		let (_, _, step) = graph.edges[0];
+
+		// When
+		let dot = graph.to_dot(&map, None);
+
+		// Then
+		assert!(dot.contains(&format!("n0 -> n1 [label=\"{}\"]", step)), "{}", dot);
+		assert!(step.to_string().ends_with(&format!(": {}", step.energy)));
+	}
+}
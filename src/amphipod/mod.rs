@@ -0,0 +1,1433 @@
+//! Solver for the amphipod burrow of day 23.
+//!
+//! A burrow is a hallway with a number of side rooms below it. Each amphipod has to be moved into
+//! the room of its own kind, and every step costs energy depending on the kind of amphipod. An
+//! amphipod leaves its room at most once, stopping somewhere in the hallway, and then moves
+//! straight into its own room.
+//!
+//! ```
+//! use aoc2021::amphipod::{easiest_moves, Map};
+//!
+//! let map: Map = [
+//!     "#############",
+//!     "#...........#",
+//!     "###B#A#C#D###",
+//!     "  #A#B#C#D#",
+//!     "  #########",
+//! ]
+//! .iter()
+//! .map(|line| line.to_string())
+//! .collect();
+//!
+//! let plan = easiest_moves(map).unwrap();
+//! assert_eq!(46, plan.energy);
+//! assert_eq!(4, plan.moves.len());
+//! ```
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::Iterator;
+use std::sync::Arc;
+use std::vec::Vec;
+
+pub mod burrow;
+#[cfg(test)]
+mod fixtures;
+pub mod frontier;
+pub mod generate;
+pub mod graph;
+pub mod parallel;
+mod parse;
+pub mod play;
+pub mod replay;
+pub mod search;
+pub mod solutions;
+pub mod state;
+pub mod validate;
+
+pub use burrow::{cave, Burrow, EnergyCostError, EnergyCosts, Room};
+pub use parallel::Parallel;
+pub use parse::MapError;
+pub use search::{AStar, SearchStrategy};
+pub use state::State;
+
+/// A square in the burrow, counted from the top left corner of the drawing.
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+pub struct Position {
+	pub y: u8,
+	pub x: u8,
+}
+
+impl Position {
+	/// Number of steps between two positions, ignoring any walls.
+	pub fn distance(&self, other: &Position) -> i32 {
+		((self.y as i32) - (other.y as i32)).abs() + ((self.x as i32) - (other.x as i32)).abs()
+	}
+}
+
+/// A single amphipod in the burrow.
+///
+/// Amphipods are ordered by kind first so a sorted list of them is the same regardless of which
+/// amphipod of a kind went where.
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+pub struct Amphipod {
+	/// The kind of amphipod, an uppercase letter.
+	pub color: char,
+	pub position: Position,
+	/// Whether the amphipod has left the room it started in.
+	pub has_moved: bool,
+}
+
+impl Amphipod {
+	/// The energy needed for each step this amphipod takes.
+	///
+	/// ## Panics
+	///
+	/// If there is no energy cost for this kind of amphipod in the burrow.
+	pub fn energy_cost(&self, burrow: &Burrow) -> i32 {
+		burrow
+			.energy_cost(self.color)
+			.unwrap_or_else(|| panic!("there is no energy cost for amphipod {}", self.color))
+	}
+
+	/// The room where this kind of amphipod belongs.
+	///
+	/// ## Panics
+	///
+	/// If there is no room for this kind of amphipod in the burrow.
+	pub fn home<'a>(&self, burrow: &'a Burrow) -> &'a Room {
+		burrow
+			.room(self.color)
+			.unwrap_or_else(|| panic!("there is no room for amphipod {}", self.color))
+	}
+
+	/// The column of the room where this kind of amphipod belongs.
+	pub fn home_column(&self, burrow: &Burrow) -> u8 {
+		self.home(burrow).x
+	}
+
+	/// The positions this amphipod can move to in a single move, given the rest of the map.
+	///
+	/// An amphipod in the hallway may only move into its own room, and only when there are no
+	/// other kinds of amphipods in it. It then goes all the way down, so it doesn't block the rest
+	/// of its kind. An amphipod that is already settled in its own room stays there.
+	pub fn possible_moves(&self, map: &Map) -> Vec<Position> {
+		let burrow = &map.burrow;
+
+		let entry;
+		let moves: &[Position] = match (burrow.in_hallway(&self.position), self.has_moved) {
+			(true, _) => {
+				entry = map.entry_square(self.home(burrow));
+				entry.as_slice()
+			}
+			(false, false) if !map.is_settled(self) => &burrow.stops,
+			(false, _) => &[],
+		};
+
+		moves
+			.iter()
+			.filter(|p| map.path_is_open(&self.position, p))
+			.copied()
+			.collect()
+	}
+}
+
+/// A single amphipod moving from one square to another.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub struct Move {
+	/// The kind of amphipod that moves.
+	pub color: char,
+	pub from: Position,
+	pub to: Position,
+	/// The energy spent on this move.
+	pub energy: i32,
+}
+
+impl fmt::Display for Move {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(
+			f,
+			"{} ({}, {}) -> ({}, {}): {}",
+			self.color, self.from.x, self.from.y, self.to.x, self.to.y, self.energy
+		)
+	}
+}
+
+/// The moves that organize a map, in order, and the total energy they need.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Plan {
+	pub energy: i32,
+	pub moves: Vec<Move>,
+}
+
+impl Plan {
+	/// The maps passed when making the moves, starting with `start` and ending with the organized
+	/// map.
+	///
+	/// Returns `None` if a move can't be made in the map it is applied to.
+	pub fn replay(&self, start: &Map) -> Option<Vec<Map>> {
+		let mut maps = vec![start.clone()];
+
+		for step in &self.moves {
+			let next = maps.last()?.apply(step)?;
+			maps.push(next);
+		}
+
+		Some(maps)
+	}
+}
+
+/// Stores the state of the Map
+///
+/// When the work is distributed between threads the maps are packed into a [`State`], which for
+/// most burrows is a single number that is cheap to send between them. See [`Parallel`].
+///
+/// The amphipods are kept sorted, so two maps with the same kinds of amphipods in the same places
+/// are equal. The layout of the burrow is shared between all the maps of the same burrow.
+#[derive(Clone, Debug)]
+pub struct Map {
+	pub burrow: Arc<Burrow>,
+	pub amphipods: Vec<Amphipod>,
+}
+
+impl PartialEq for Map {
+	fn eq(&self, other: &Self) -> bool {
+		self.amphipods == other.amphipods && (Arc::ptr_eq(&self.burrow, &other.burrow) || self.burrow == other.burrow)
+	}
+}
+
+impl Eq for Map {}
+
+impl Hash for Map {
+	/// Only the amphipods are hashed, maps are rarely compared between different burrows.
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.amphipods.hash(state);
+	}
+}
+
+impl Map {
+	/// Create a map of amphipods in the burrow.
+	pub fn new(burrow: Arc<Burrow>, mut amphipods: Vec<Amphipod>) -> Self {
+		amphipods.sort();
+
+		Map { burrow, amphipods }
+	}
+
+	/// Determine if it is possible to move from start to goal
+	///
+	/// This needs to take into account if there is another Amphipod in the way.
+	/// Since this is used to find the way for a particular Ampipod some simplification can
+	/// probably be made by ignoring if there's actually a 'pod at the starting point.
+	pub fn path_is_open(&self, start: &Position, goal: &Position) -> bool {
+		// The initial idea was to find all the Positions in the traveled path and then try to se
+		// if there's an Amphipod in the way.
+		// It might be better for performance to create a set of the existing occupied positions
+		// and then try to "travel the path" (try each position in turn) to see if any is occupied
+		// (exists already in the set of positions).
+
+		let path_position = self.path(start, goal);
+
+		!self
+			.amphipods
+			.iter()
+			.any(|amphipod| path_position.contains(&amphipod.position))
+	}
+
+	/// The positions passed when moving from start to goal, excluding start.
+	///
+	/// The path always goes through the hallway, so moving from one room to another first goes up
+	/// out of the room and then down into the other one.
+	pub fn path(&self, start: &Position, goal: &Position) -> Vec<Position> {
+		let hallway_y = self.burrow.hallway_y;
+
+		if start.y > hallway_y && goal.y > hallway_y && start.x != goal.x {
+			let corner = Position { x: start.x, y: hallway_y };
+
+			let mut positions = self.path(start, &corner);
+			positions.extend(self.path(&corner, goal));
+
+			return positions;
+		}
+
+		let horisontal_positions = match start.x < goal.x {
+			true => (start.x + 1)..=(goal.x),
+			false => (goal.x)..=(start.x - 1),
+		}
+		.map(|x| Position {
+			x,
+			y: hallway_y,
+		});
+
+		let (x, ys) = match start.y > goal.y {
+			true => (start.x, (goal.y)..=(start.y - 1)),
+			false => (goal.x, (start.y + 1)..=(goal.y)),
+		};
+
+		let vertical_positions = ys.map(|y| Position { x, y });
+
+		horisontal_positions.chain(vertical_positions).collect()
+	}
+
+	/// The amphipod at `position`, if any.
+	pub fn occupant(&self, position: &Position) -> Option<&Amphipod> {
+		self.amphipods.iter().find(|amphipod| amphipod.position == *position)
+	}
+
+	/// Whether the room only holds amphipods that belong there.
+	pub fn room_is_clean(&self, room: &Room) -> bool {
+		room.squares
+			.iter()
+			.filter_map(|square| self.occupant(square))
+			.all(|amphipod| amphipod.color == room.color)
+	}
+
+	/// The square an amphipod moving into the room would go to: the deepest free square.
+	///
+	/// Returns `None` if the room is full or holds amphipods that don't belong there.
+	pub fn entry_square(&self, room: &Room) -> Option<Position> {
+		if !self.room_is_clean(room) {
+			return None;
+		}
+
+		room.squares
+			.iter()
+			.rev()
+			.find(|square| self.occupant(square).is_none())
+			.copied()
+	}
+
+	/// Whether the amphipod is in its own room with only its own kind below it.
+	///
+	/// A settled amphipod never has to move again.
+	pub fn is_settled(&self, amphipod: &Amphipod) -> bool {
+		let home = amphipod.home(&self.burrow);
+
+		home.contains(&amphipod.position)
+			&& home
+				.squares
+				.iter()
+				.filter(|square| square.y > amphipod.position.y)
+				.all(|square| self.occupant(square).is_some_and(|other| other.color == amphipod.color))
+	}
+
+	/// Whether every amphipod is in its own room.
+	///
+	/// Since there are as many amphipods of each kind as there are squares in a room, the rooms
+	/// are full when every amphipod is in one of the squares of its own room.
+	pub fn amphipods_organized(&self) -> bool {
+		self.amphipods
+			.iter()
+			.all(|amphipod| amphipod.home(&self.burrow).contains(&amphipod.position))
+	}
+
+	/// Whether two amphipods in the hallway are each in the other's way home.
+	///
+	/// Amphipods in the hallway only move into their own room, so they can never get past each
+	/// other and the amphipods can't be organized from here.
+	pub fn is_deadlocked(&self) -> bool {
+		let mut hallway: Vec<(u8, u8)> = self
+			.amphipods
+			.iter()
+			.filter(|amphipod| self.burrow.in_hallway(&amphipod.position))
+			.map(|amphipod| (amphipod.position.x, amphipod.home_column(&self.burrow)))
+			.collect();
+		hallway.sort_unstable();
+
+		hallway.iter().enumerate().any(|(i, (left, left_home))| {
+			hallway[i + 1..]
+				.iter()
+				.any(|(right, right_home)| left_home > right && right_home < left)
+		})
+	}
+
+	/// The map after making `step`, or `None` if there is no such amphipod where the move starts.
+	///
+	/// This doesn't check that the move is allowed.
+	pub fn apply(&self, step: &Move) -> Option<Self> {
+		let index = self
+			.amphipods
+			.iter()
+			.position(|amphipod| amphipod.position == step.from && amphipod.color == step.color)?;
+
+		Some(self.moved(index, step.to))
+	}
+
+	/// The maps that can be reached in a single move, together with the move that reaches them.
+	///
+	/// This is what every search strategy explores the burrow with.
+	pub fn successors(&self) -> Vec<(Move, Map)> {
+		self.amphipods
+			.iter()
+			.enumerate()
+			.flat_map(|(n, amphipod)| {
+				let energy_cost = amphipod.energy_cost(&self.burrow);
+
+				amphipod.possible_moves(self).into_iter().map(move |goal| {
+					let step = Move {
+						color: amphipod.color,
+						from: amphipod.position,
+						to: goal,
+						energy: amphipod.position.distance(&goal) * energy_cost,
+					};
+
+					(step, self.moved(n, goal))
+				})
+			})
+			.collect()
+	}
+
+	/// Move the amphipod at `index` to `goal`, keeping the amphipods sorted.
+	fn moved(&self, index: usize, goal: Position) -> Self {
+		let mut map = self.clone();
+		map.amphipods[index].position = goal;
+		map.amphipods[index].has_moved = true;
+		map.amphipods.sort();
+
+		map
+	}
+}
+
+impl fmt::Display for Map {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		let mut rows = self.burrow.drawing.clone();
+
+		for amphipod in &self.amphipods {
+			let position = &amphipod.position;
+
+			rows[position.y as usize][position.x as usize] = amphipod.color;
+		}
+
+		for row in rows {
+			writeln!(f, "{}", row.into_iter().collect::<String>())?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Return the heuristic for finishing this map.
+///
+/// Since this is used for prioritizing which states that should be examined in the A*-algorithm
+/// this needs to be close to the actual cost of finishing this map and never higher than the
+/// actual cost.
+///
+/// Every amphipod that isn't settled has to go up into the hallway, across to its own room and
+/// down into it. One that is in its own room, on top of a stranger, has to step aside and back
+/// again. The amphipods moving into a room fill it from the bottom of the free squares, so one of
+/// them goes all the way down to those, the next one square less and so on.
+pub fn heuristic(map: &Map) -> i32 {
+	let burrow = &map.burrow;
+	let hallway_y = burrow.hallway_y as i32;
+
+	// The number of amphipods settled at the bottom of each room.
+	let settled: Vec<usize> = burrow
+		.rooms
+		.iter()
+		.map(|room| {
+			room.squares
+				.iter()
+				.rev()
+				.take_while(|square| map.occupant(square).is_some_and(|other| other.color == room.color))
+				.count()
+		})
+		.collect();
+
+	// The number of amphipods moving into each room so far.
+	let mut entering = vec![0; burrow.rooms.len()];
+
+	map.amphipods
+		.iter()
+		.map(|amphipod| {
+			let index = burrow
+				.rooms
+				.iter()
+				.position(|room| room.color == amphipod.color)
+				.unwrap_or_else(|| panic!("there is no room for amphipod {}", amphipod.color));
+			let home = &burrow.rooms[index];
+			let position = amphipod.position;
+
+			if home.contains(&position) && position.y as i32 > hallway_y + (home.depth() - settled[index]) as i32 {
+				return 0;
+			}
+			entering[index] += 1;
+
+			let up = position.y as i32 - hallway_y;
+			let across = match (up, (position.x as i32 - home.x as i32).abs()) {
+				(1.., 0) => 2,
+				(_, across) => across,
+			};
+			let down = entering[index];
+
+			(up + across + down) * amphipod.energy_cost(burrow)
+		})
+		.sum()
+}
+
+/// Find the cheapest way to organize the amphipods.
+///
+/// This is an A*-search over the possible states of the map, guided by [`heuristic`]. Returns
+/// `None` if the amphipods can't be organized at all. See [`search`] for other ways to search.
+pub fn easiest_moves(map: Map) -> Option<Plan> {
+	AStar.search(&map).plan
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	mod position {
+		use super::*;
+
+		#[test]
+		fn test_distance_to_self_should_be_zero() {
+			// Given
+			let input = Position { x: 42, y: 13 };
+
+			// When
+			let result = input.distance(&input);
+
+			// Then
+			assert_eq!(0, result);
+		}
+
+		#[test]
+		fn test_distance_to_right_position_should_be_positive() {
+			// Given
+			let left = Position { x: 2, y: 2 };
+			let right = Position { x: 5, y: 2 };
+
+			// When
+			let result = left.distance(&right);
+
+			// Then
+			assert_eq!(3, result);
+		}
+
+		#[test]
+		fn test_distance_to_left_position_should_be_positive() {
+			// Given
+			let right = Position { x: 5, y: 2 };
+			let left = Position { x: 2, y: 2 };
+
+			// When
+			let result = right.distance(&left);
+
+			// Then
+			assert_eq!(3, result);
+		}
+
+		#[test]
+		fn test_diagonal_distance_should_use_manhattan_distance() {
+			// Given
+			let right = Position { x: 5, y: 1 };
+			let left = Position { x: 2, y: 3 };
+
+			// When
+			let result = right.distance(&left);
+
+			// Then
+			assert_eq!(5, result);
+		}
+	}
+
+	mod map {
+		use super::*;
+
+		mod distance {
+			use super::*;
+
+			#[test]
+			fn test_same_start_and_goal_should_generate_empty_path() {
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 1, y: 1 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &start);
+
+				// Then
+				let expected: Vec<Position> = vec![];
+				assert_eq!(expected, result);
+			}
+
+			#[test]
+			fn test_start_single_step_left_of_goal_should_have_single_position() {
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 1, y: 1 };
+				let goal = Position { x: 2, y: 1 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &goal);
+
+				// Then
+				let expected: Vec<Position> = vec![Position { x: 2, y: 1 }];
+				assert_eq!(expected, result);
+			}
+
+			#[test]
+			fn test_start_single_step_right_of_goal_should_have_single_position() {
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 2, y: 1 };
+				let goal = Position { x: 1, y: 1 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &goal);
+
+				// Then
+				let expected: Vec<Position> = vec![Position { x: 1, y: 1 }];
+				assert_eq!(expected, result);
+			}
+
+			#[test]
+			fn test_start_single_step_over_goal_should_have_single_position() {
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 3, y: 1 };
+				let goal = Position { x: 3, y: 2 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &goal);
+
+				// Then
+				let expected: Vec<Position> = vec![Position { x: 3, y: 2 }];
+				assert_eq!(expected, result);
+			}
+
+			#[test]
+			fn test_start_single_step_under_goal_should_have_single_position() {
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 3, y: 2 };
+				let goal = Position { x: 3, y: 1 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &goal);
+
+				// Then
+				let expected: Vec<Position> = vec![Position { x: 3, y: 1 }];
+				assert_eq!(expected, result);
+			}
+
+			#[test]
+			fn test_up_up_then_left_should_have_three_positions() {
+				//! #############
+				//! #.Gx........#
+				//! ###x#.#.#.###
+				//! ###S#.#.#.###
+				//! #############
+
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 3, y: 3 };
+				let goal = Position { x: 2, y: 1 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &goal);
+
+				// Then
+				let expected: Vec<Position> = vec![
+					Position { x: 2, y: 1 },
+					Position { x: 3, y: 1 },
+					Position { x: 3, y: 2 },
+				];
+				assert_eq!(expected, result);
+			}
+
+			#[test]
+			fn test_left_left_then_down_down_should_have_four_positions() {
+				//! #############
+				//! #........xxS#
+				//! ###.#.#.#x###
+				//! ###.#.#.#G###
+				//! #############
+
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 12, y: 1 };
+				let goal = Position { x: 10, y: 3 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &goal);
+
+				// Then
+				let expected: Vec<Position> = vec![
+					Position { x: 10, y: 1 },
+					Position { x: 11, y: 1 },
+					Position { x: 10, y: 2 },
+					Position { x: 10, y: 3 },
+				];
+				assert_eq!(expected, result);
+			}
+			#[test]
+			fn test_room_to_room_should_go_up_across_and_down() {
+				//! #############
+				//! #..xxx......#
+				//! ###S#x#.#.###
+				//! ###.#G#.#.###
+				//! #############
+
+				// Given
+				let map = Map::new(Arc::new(Burrow::standard(2)), vec![]);
+
+				let start = Position { x: 3, y: 2 };
+				let goal = Position { x: 5, y: 3 };
+
+				// When
+				let result: Vec<Position> = map.path(&start, &goal);
+
+				// Then
+				let expected: Vec<Position> = vec![
+					Position { x: 3, y: 1 },
+					Position { x: 4, y: 1 },
+					Position { x: 5, y: 1 },
+					Position { x: 5, y: 2 },
+					Position { x: 5, y: 3 },
+				];
+				assert_eq!(expected, result);
+			}
+		}
+
+		mod path_is_open {
+			use super::*;
+
+			#[test]
+			fn test_open_path_should_be_true() {
+				//! #############
+				//! #Sxxxx..B...#
+				//! ###.#x#.#.###
+				//! ###.#G#.#.###
+				//! #############
+
+				// Given
+				let map = Map::new(
+					Arc::new(Burrow::standard(2)),
+					vec![Amphipod {
+						position: Position { x: 9, y: 1 },
+						color: 'B',
+						has_moved: true,
+					}],
+				);
+
+				let start = Position { x: 1, y: 1 };
+				let goal = Position { x: 6, y: 3 };
+
+				// When
+				let result = map.path_is_open(&start, &goal);
+
+				// Then
+				assert!(result)
+			}
+
+			#[test]
+			fn test_blocked_path_should_be_false() {
+				//! #############
+				//! #SxxBx......#
+				//! ###.#x#.#.###
+				//! ###.#G#.#.###
+				//! #############
+
+				// Given
+				let map = Map::new(
+					Arc::new(Burrow::standard(2)),
+					vec![Amphipod {
+						position: Position { x: 5, y: 1 },
+						color: 'B',
+						has_moved: true,
+					}],
+				);
+
+				let start = Position { x: 1, y: 1 };
+				let goal = Position { x: 6, y: 3 };
+
+				// When
+				let result = map.path_is_open(&start, &goal);
+
+				// Then
+				assert!(!result)
+			}
+		}
+
+		mod amphipods_organized {
+			use super::*;
+
+			#[test]
+			fn test_all_amphipods_in_their_correct_place_should_be_organized() {
+				// Given
+				let map: Map = vec![
+					"#############",
+					"#...........#",
+					"###A#B#C#D###",
+					"  #A#B#C#D#",
+					"  #########",
+				]
+				.into_iter()
+				.map(String::from)
+				.collect();
+
+				// When
+				let result = map.amphipods_organized();
+
+				// Then
+				assert!(result);
+			}
+
+			#[test]
+			fn test_all_misplaced_amphipod_should_be_not_organized() {
+				// Given
+				let map: Map = vec![
+					"#############",
+					"#...........#",
+					"###B#A#C#D###",
+					"  #A#B#C#D#",
+					"  #########",
+				]
+				.into_iter()
+				.map(String::from)
+				.collect();
+
+				// When
+				let result = map.amphipods_organized();
+
+				// Then
+				assert!(!result);
+			}
+		}
+	}
+
+	mod depth {
+		use super::*;
+
+		#[test]
+		fn test_depth_should_be_read_from_the_drawing() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###B#C#B#D###",
+				"  #D#C#B#A#",
+				"  #D#B#A#C#",
+				"  #A#D#C#A#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result: Vec<usize> = map.burrow.rooms.iter().map(Room::depth).collect();
+
+			// Then
+			assert_eq!(vec![4, 4, 4, 4], result);
+		}
+
+		#[test]
+		fn test_deep_rooms_in_their_correct_place_should_be_organized() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###A#B#C#D###",
+				"  #A#B#C#D#",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = map.amphipods_organized();
+
+			// Then
+			assert!(result);
+		}
+
+		#[test]
+		fn test_display_should_draw_rooms_of_the_same_depth() {
+			// Given
+			let drawing = [
+				"#############",
+				"#.....D.....#",
+				"###A#B#C#.###",
+				"  #A#B#C#D#",
+				"  #A#B#C#D#",
+				"  #########",
+			];
+			let map: Map = drawing.iter().map(|line| line.to_string()).collect();
+
+			// When
+			let result = map.to_string();
+
+			// Then
+			assert_eq!(drawing.join("\n") + "\n", result);
+		}
+
+		#[test]
+		fn test_deep_example_should_be_solved() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###B#A#C#D###",
+				"  #A#B#C#D#",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = easiest_moves(map).unwrap();
+
+			// Then
+			assert_eq!(46, result.energy);
+		}
+	}
+
+	mod layout {
+		use super::*;
+
+		#[test]
+		fn test_unevenly_spaced_rooms_should_be_solved() {
+			// Given
+			let map: Map = vec![
+				"##############",
+				"#............#",
+				"###B##A#C#####",
+				"  #A##B#C#",
+				"  ########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = easiest_moves(map).unwrap();
+
+			// Then
+			assert_eq!(57, result.energy);
+		}
+
+		#[test]
+		fn test_amphipod_should_not_stop_outside_a_room() {
+			// Given
+			let map: Map = vec![
+				"##########",
+				"#........#",
+				"###B##A###",
+				"  ######",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = map.amphipods[1].possible_moves(&map);
+
+			// Then
+			let columns: Vec<u8> = result.iter().map(|p| p.x).collect();
+			assert_eq!(vec![1, 2, 4, 5, 7, 8], columns);
+		}
+	}
+
+	mod species {
+		use super::*;
+
+		#[test]
+		fn test_five_kinds_with_given_costs_should_be_solved() {
+			// Given
+			let map: Map = vec![
+				"###############",
+				"#.............#",
+				"###A#B#C#E#D###",
+				"  ###########",
+				"",
+				"E=10000",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = easiest_moves(map).unwrap();
+
+			// Then
+			assert_eq!(4 * 10000 + 6 * 1000, result.energy);
+		}
+
+		#[test]
+		fn test_custom_costs_should_change_the_energy() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###B#A#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+				"",
+				"A=3 B=5",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = easiest_moves(map).unwrap();
+
+			// Then
+			assert_eq!(38, result.energy);
+		}
+
+		#[test]
+		fn test_other_letters_should_be_kinds_of_amphipods() {
+			// Given
+			let map: Map = vec![
+				"#########",
+				"#.......#",
+				"###X#W###",
+				"  #W#X#",
+				"  #####",
+				"",
+				"W=1 X=10",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = easiest_moves(map).unwrap();
+
+			// Then
+			assert_eq!(46, result.energy);
+		}
+	}
+
+	mod possible_moves {
+		use super::*;
+
+		fn map(lines: &[&str]) -> Map {
+			lines.iter().map(|line| line.to_string()).collect()
+		}
+
+		fn moves_of(map: &Map, position: Position) -> Vec<Position> {
+			map.occupant(&position).unwrap().possible_moves(map)
+		}
+
+		#[test]
+		fn test_amphipod_should_not_enter_room_with_strangers() {
+			// Given
+			let map = map(&[
+				"#############",
+				"#.A.........#",
+				"###.#B#C#D###",
+				"  #B#A#C#D#",
+				"  #########",
+			]);
+
+			// When
+			let result = moves_of(&map, Position { x: 2, y: 1 });
+
+			// Then
+			assert_eq!(Vec::<Position>::new(), result);
+		}
+
+		#[test]
+		fn test_amphipod_should_go_to_the_bottom_of_an_empty_room() {
+			// Given
+			let map = map(&[
+				"#############",
+				"#.A.......A.#",
+				"###.#B#C#D###",
+				"  #.#B#C#D#",
+				"  #########",
+			]);
+
+			// When
+			let result = moves_of(&map, Position { x: 2, y: 1 });
+
+			// Then
+			assert_eq!(vec![Position { x: 3, y: 3 }], result);
+		}
+
+		#[test]
+		fn test_amphipod_should_stack_on_its_own_kind() {
+			// Given
+			let map = map(&[
+				"#############",
+				"#.A.........#",
+				"###.#B#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+			]);
+
+			// When
+			let result = moves_of(&map, Position { x: 2, y: 1 });
+
+			// Then
+			assert_eq!(vec![Position { x: 3, y: 2 }], result);
+		}
+
+		#[test]
+		fn test_settled_amphipod_should_not_move() {
+			// Given
+			let map = map(&[
+				"#############",
+				"#...........#",
+				"###B#.#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+			]);
+
+			// When
+			let result = moves_of(&map, Position { x: 3, y: 3 });
+
+			// Then
+			assert_eq!(Vec::<Position>::new(), result);
+		}
+
+		#[test]
+		fn test_amphipod_blocking_a_stranger_should_move_out() {
+			// Given
+			let map = map(&[
+				"#############",
+				"#...........#",
+				"###A#B#C#D###",
+				"  #B#A#C#D#",
+				"  #########",
+			]);
+
+			// When
+			let result = moves_of(&map, Position { x: 3, y: 2 });
+
+			// Then
+			assert_eq!(7, result.len());
+		}
+
+		#[test]
+		fn test_search_should_never_enter_a_room_with_strangers() {
+			// Given
+			let start = map(&[
+				"#############",
+				"#...........#",
+				"###B#C#B#D###",
+				"  #A#D#C#A#",
+				"  #########",
+			]);
+			let plan = easiest_moves(start.clone()).unwrap();
+
+			// When
+			let result = plan.replay(&start).unwrap();
+
+			// Then
+			for (before, step) in result.iter().zip(&plan.moves) {
+				if let Some(room) = before.burrow.room_at(&step.to) {
+					assert!(before.room_is_clean(room), "{}entered by {}", before, step);
+				}
+			}
+		}
+	}
+
+	mod plan {
+		use super::*;
+		use crate::amphipod::fixtures::swapped_rooms;
+
+		#[test]
+		fn test_energy_should_be_the_sum_of_the_moves() {
+			// Given
+			let map = swapped_rooms();
+
+			// When
+			let result = easiest_moves(map).unwrap();
+
+			// Then
+			let energy: i32 = result.moves.iter().map(|step| step.energy).sum();
+			assert_eq!(result.energy, energy);
+		}
+
+		#[test]
+		fn test_replay_should_end_organized() {
+			// Given
+			let map = swapped_rooms();
+			let plan = easiest_moves(map.clone()).unwrap();
+
+			// When
+			let result = plan.replay(&map).unwrap();
+
+			// Then
+			assert_eq!(plan.moves.len() + 1, result.len());
+			assert_eq!(Some(&map), result.first());
+			assert!(result.last().unwrap().amphipods_organized());
+		}
+
+		#[test]
+		fn test_replay_of_move_without_amphipod_should_fail() {
+			// Given
+			let map = swapped_rooms();
+			let plan = Plan {
+				energy: 2,
+				moves: vec![Move {
+					color: 'A',
+					from: Position { x: 1, y: 1 },
+					to: Position { x: 2, y: 1 },
+					energy: 1,
+				}],
+			};
+
+			// When
+			let result = plan.replay(&map);
+
+			// Then
+			assert_eq!(None, result);
+		}
+
+		#[test]
+		fn test_organized_map_should_need_no_moves() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###A#B#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = easiest_moves(map).unwrap();
+
+			// Then
+			assert_eq!(Plan { energy: 0, moves: vec![] }, result);
+		}
+	}
+
+	mod deadlock {
+		use super::*;
+
+		#[test]
+		fn test_amphipods_in_each_others_way_should_be_deadlocked() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...D.A.....#",
+				"###.#B#C#.###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = map.is_deadlocked();
+
+			// Then
+			assert!(result);
+		}
+
+		#[test]
+		fn test_amphipods_going_the_same_way_should_not_be_deadlocked() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#.....A.D...#",
+				"###.#B#C#.###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = map.is_deadlocked();
+
+			// Then
+			assert!(!result);
+		}
+
+		#[test]
+		fn test_amphipod_waiting_for_another_should_not_be_deadlocked() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#.B.D.......#",
+				"###A#.#C#.###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = map.is_deadlocked();
+
+			// Then
+			assert!(!result);
+		}
+
+		#[test]
+		fn test_deadlocked_maps_should_be_pruned() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###B#C#B#D###",
+				"  #A#D#C#A#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = AStar.search(&map);
+
+			// Then
+			assert!(result.stats.pruned > 0);
+			assert_eq!(Some(12521), result.plan.map(|plan| plan.energy));
+		}
+	}
+
+	mod heuristic {
+		use super::*;
+
+		#[test]
+		fn goal_state_should_be_zero() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###A#B#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = heuristic(&map);
+
+			// Then
+			assert_eq!(0, result);
+		}
+
+		#[test]
+		fn amber_amphipod_out_of_place_should_be_2() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#.A.........#",
+				"###.#B#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = heuristic(&map);
+
+			// Then
+			assert_eq!(2, result);
+		}
+
+		#[test]
+		fn bronze_amphipod_out_of_place_should_be_correct() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#.........B.#",
+				"###A#.#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = heuristic(&map);
+
+			// Then
+			assert_eq!(60, result);
+		}
+
+		#[test]
+		fn switching_rooms_should_be_correct() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###B#A#C#D###",
+				"  #A#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = heuristic(&map);
+
+			// Then
+			assert_eq!(44, result);
+		}
+
+		#[test]
+		fn amphipods_entering_a_room_should_stack() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#.A.A.......#",
+				"###.#B#C#D###",
+				"  #.#B#C#D#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = heuristic(&map);
+
+			// Then
+			assert_eq!(1 + 2 + 1 + 1, result);
+		}
+
+		#[test]
+		fn amphipods_on_top_of_strangers_should_step_aside() {
+			// Given
+			let map: Map = vec![
+				"#############",
+				"#...........#",
+				"###A#B#C#D###",
+				"  #D#B#C#A#",
+				"  #########",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect();
+
+			// When
+			let result = heuristic(&map);
+
+			// Then
+			assert_eq!((1 + 2 + 1) + (2 + 6 + 2) + (1 + 2 + 1) * 1000 + (2 + 6 + 2) * 1000, result);
+		}
+	}
+}
@@ -0,0 +1,420 @@
+//! Searching for the cheapest plan with several threads.
+//!
+//! This is hash distributed A*: every map belongs to one of the threads, picked by the hash of the
+//! map. Each thread keeps its own queue of the maps it owns, ordered by estimated total cost like
+//! [`AStar`](super::AStar), and the cheapest known way to reach each of them, so no queue or table
+//! is shared. A thread that reaches a map belonging to another thread sends it to the inbox of that
+//! thread, and the inboxes are all the threads ever lock. The energy of the cheapest plan found so
+//! far is shared as well, and no thread bothers with maps that can't lead to anything cheaper.
+//!
+//! The table of maps reached is split between the threads on purpose, rather than shared behind
+//! a lock. A map only ever has an entry with the thread it belongs to, and every way of reaching it
+//! is handed to that thread, so all its costs are still compared in one place, just as they would
+//! be in a single shared table. The threads only wait on each other to hand over maps.
+//!
+//! A thread may get a cheaper way to a map after it has already expanded it. The map is then
+//! simply expanded again. The search is over when every thread has run out of maps worth expanding
+//! and there are no maps left in any inbox. The plan found is then the cheapest one as long as the
+//! heuristic never overestimates: along a cheaper plan there is always a map that has been reached
+//! as cheaply as the plan reaches it but not expanded since, and its estimated total is below the
+//! energy of the plan found, so it would still be worth expanding.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BinaryHeap;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use super::search::{plan_to, CameFrom, Progress, Search, SearchStats, SearchStrategy, PROGRESS_INTERVAL};
+use super::{heuristic, Map, Move, State};
+
+/// A* search with a number of threads.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Parallel {
+	pub threads: NonZeroUsize,
+}
+
+impl Parallel {
+	/// Search with `threads` threads, or a single one if `threads` is 0.
+	pub fn new(threads: usize) -> Self {
+		Parallel {
+			threads: NonZeroUsize::new(threads).unwrap_or(NonZeroUsize::MIN),
+		}
+	}
+}
+
+impl Default for Parallel {
+	/// Search with as many threads as there are processors.
+	fn default() -> Self {
+		Parallel {
+			threads: thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+		}
+	}
+}
+
+/// A map reached with `cost` through a move from another map, unless it's the start, and
+/// estimated to need `estimate` more to organize.
+struct Reached {
+	state: State,
+	from: Option<(State, Move)>,
+	cost: i32,
+	estimate: i32,
+}
+
+/// The maps sent to a thread by the others.
+#[derive(Default)]
+struct Inbox {
+	maps: Mutex<Vec<Reached>>,
+	/// Signalled when maps are sent, or the search is over.
+	arrived: Condvar,
+}
+
+/// The maps a thread owns.
+#[derive(Default)]
+struct Owned {
+	/// The maps to expand, with their estimated total cost and the cost they were reached with.
+	queue: BinaryHeap<Reverse<(i32, i32, State)>>,
+	came_from: CameFrom,
+}
+
+/// The statistics of the search, counted by all the threads.
+#[derive(Default)]
+struct Stats {
+	expanded: AtomicUsize,
+	pushed: AtomicUsize,
+	/// The number of maps in all the queues together.
+	queued: AtomicUsize,
+	peak_queue: AtomicUsize,
+	visited: AtomicUsize,
+	duplicates: AtomicUsize,
+	goal_hits: AtomicUsize,
+	pruned: AtomicUsize,
+}
+
+impl Stats {
+	fn count(counter: &AtomicUsize) {
+		counter.fetch_add(1, Ordering::Relaxed);
+	}
+}
+
+/// Everything the threads share.
+struct Shared<'a> {
+	inboxes: Vec<Inbox>,
+	/// The number of threads that have maps to expand, plus the number of maps sent that haven't
+	/// been taken out of an inbox yet. The search is over once this is 0.
+	working: AtomicUsize,
+	/// The energy of the cheapest plan found so far.
+	bound: AtomicI32,
+	/// The organized map of the cheapest plan found so far.
+	goal: Mutex<Option<State>>,
+	stats: Stats,
+	started: Instant,
+	progress: Mutex<Progress<'a>>,
+}
+
+impl Shared<'_> {
+	/// The statistics so far.
+	fn stats(&self) -> SearchStats {
+		let stats = &self.stats;
+
+		SearchStats {
+			expanded: stats.expanded.load(Ordering::Relaxed),
+			pushed: stats.pushed.load(Ordering::Relaxed),
+			peak_queue: stats.peak_queue.load(Ordering::Relaxed),
+			visited: stats.visited.load(Ordering::Relaxed),
+			duplicates: stats.duplicates.load(Ordering::Relaxed),
+			goal_hits: stats.goal_hits.load(Ordering::Relaxed),
+			pruned: stats.pruned.load(Ordering::Relaxed),
+			best: Some(self.bound.load(Ordering::Relaxed)).filter(|bound| *bound < i32::MAX),
+			elapsed: self.started.elapsed(),
+		}
+	}
+
+	/// The thread that owns `state`.
+	fn owner(&self, state: &State) -> usize {
+		let mut hasher = DefaultHasher::new();
+		state.hash(&mut hasher);
+
+		hasher.finish() as usize % self.inboxes.len()
+	}
+
+	/// Send a map to the inbox of the thread that owns it.
+	fn send(&self, owner: usize, reached: Reached) {
+		// Counted before it's in the inbox, while the sender is still working, so the count can't
+		// drop to 0 in between.
+		self.working.fetch_add(1, Ordering::AcqRel);
+
+		let inbox = &self.inboxes[owner];
+		inbox.maps.lock().unwrap().push(reached);
+		inbox.arrived.notify_one();
+	}
+
+	/// Keep a map the thread owns, if it was reached more cheaply than before.
+	fn receive(&self, owned: &mut Owned, reached: Reached) {
+		let Reached {
+			state,
+			from,
+			cost,
+			estimate,
+		} = reached;
+
+		if owned.came_from.get(&state).is_some_and(|(known, _)| cost >= *known) {
+			Stats::count(&self.stats.duplicates);
+			return;
+		}
+		if owned.came_from.insert(state.clone(), (cost, from)).is_none() {
+			Stats::count(&self.stats.visited);
+		}
+
+		owned.queue.push(Reverse((cost + estimate, cost, state)));
+		Stats::count(&self.stats.pushed);
+		let queued = self.stats.queued.fetch_add(1, Ordering::Relaxed) + 1;
+		self.stats.peak_queue.fetch_max(queued, Ordering::Relaxed);
+	}
+
+	/// Wait until maps arrive in the inbox of thread `id`, which has no maps left worth expanding.
+	///
+	/// Returns `false` if the search is over instead.
+	fn wait(&self, id: usize) -> bool {
+		let inbox = &self.inboxes[id];
+		let mut maps = inbox.maps.lock().unwrap();
+		if !maps.is_empty() {
+			return true;
+		}
+
+		if self.working.fetch_sub(1, Ordering::AcqRel) == 1 {
+			drop(maps);
+			// Every other thread is waiting, or about to.
+			for inbox in &self.inboxes {
+				let _maps = inbox.maps.lock().unwrap();
+				inbox.arrived.notify_all();
+			}
+			return false;
+		}
+
+		loop {
+			if !maps.is_empty() {
+				// The maps in the inbox are still counted, so the count isn't 0.
+				self.working.fetch_add(1, Ordering::AcqRel);
+				return true;
+			}
+			if self.working.load(Ordering::Acquire) == 0 {
+				return false;
+			}
+			maps = inbox.arrived.wait(maps).unwrap();
+		}
+	}
+
+	/// Expand a map reached with `cost`, keeping the maps worth searching from there that the
+	/// thread owns and sending the others to their owners.
+	fn expand(&self, id: usize, owned: &mut Owned, map: &Map, state: &State, cost: i32) {
+		if map.amphipods_organized() {
+			Stats::count(&self.stats.goal_hits);
+			let mut goal = self.goal.lock().unwrap();
+			if cost < self.bound.load(Ordering::Acquire) {
+				self.bound.store(cost, Ordering::Release);
+				*goal = Some(state.clone());
+
+				let stats = self.stats();
+				(self.progress.lock().unwrap())(&stats);
+			}
+
+			return;
+		}
+
+		for (step, next) in map.successors() {
+			if next.is_deadlocked() {
+				Stats::count(&self.stats.pruned);
+				continue;
+			}
+
+			let next_cost = cost + step.energy;
+			let estimate = heuristic(&next);
+			if next_cost + estimate >= self.bound.load(Ordering::Acquire) {
+				continue;
+			}
+
+			let next = next.pack();
+			let reached = Reached {
+				from: Some((state.clone(), step)),
+				cost: next_cost,
+				estimate,
+				state: next,
+			};
+			match self.owner(&reached.state) {
+				owner if owner == id => self.receive(owned, reached),
+				owner => self.send(owner, reached),
+			}
+		}
+	}
+
+	/// Expand the maps thread `id` owns until the search is over, returning the cheapest known
+	/// ways to them.
+	fn work(&self, id: usize, start: &Map) -> CameFrom {
+		let mut owned = Owned::default();
+
+		loop {
+			let arrived = mem::take(&mut *self.inboxes[id].maps.lock().unwrap());
+			let count = arrived.len();
+			for reached in arrived {
+				self.receive(&mut owned, reached);
+			}
+			self.working.fetch_sub(count, Ordering::AcqRel);
+
+			let Some(Reverse((estimate, cost, state))) = owned.queue.pop() else {
+				match self.wait(id) {
+					true => continue,
+					false => break,
+				}
+			};
+			self.stats.queued.fetch_sub(1, Ordering::Relaxed);
+
+			if estimate >= self.bound.load(Ordering::Acquire) {
+				// Everything else in the queue is estimated to cost even more.
+				self.stats.queued.fetch_sub(owned.queue.len(), Ordering::Relaxed);
+				owned.queue.clear();
+				continue;
+			}
+
+			// A cheaper way to the map may have been found since it was queued.
+			if owned.came_from.get(&state).is_some_and(|(known, _)| *known < cost) {
+				Stats::count(&self.stats.duplicates);
+				continue;
+			}
+
+			let expanded = self.stats.expanded.fetch_add(1, Ordering::Relaxed) + 1;
+			if expanded.is_multiple_of(PROGRESS_INTERVAL) {
+				let stats = self.stats();
+				(self.progress.lock().unwrap())(&stats);
+			}
+
+			self.expand(id, &mut owned, &state.unpack(&start.burrow), &state, cost);
+		}
+
+		owned.came_from
+	}
+}
+
+impl SearchStrategy for Parallel {
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search {
+		let threads = self.threads.get();
+
+		let shared = Shared {
+			inboxes: (0..threads).map(|_| Inbox::default()).collect(),
+			working: AtomicUsize::new(threads),
+			bound: AtomicI32::new(i32::MAX),
+			goal: Mutex::new(None),
+			stats: Stats::default(),
+			started: Instant::now(),
+			progress: Mutex::new(progress),
+		};
+		let state = start.pack();
+		let reached = Reached {
+			state,
+			from: None,
+			cost: 0,
+			estimate: heuristic(start),
+		};
+		shared.send(shared.owner(&reached.state), reached);
+
+		let came_from = thread::scope(|scope| {
+			let shared = &shared;
+			let workers: Vec<_> = (0..threads).map(|id| scope.spawn(move || shared.work(id, start))).collect();
+
+			workers.into_iter().fold(CameFrom::new(), |mut came_from, worker| {
+				came_from.extend(worker.join().unwrap());
+				came_from
+			})
+		});
+
+		let stats = shared.stats();
+		let plan = shared.goal.into_inner().unwrap().map(|goal| plan_to(&came_from, &goal));
+
+		Search {
+			plan,
+			optimal: true,
+			stats,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::fixtures::{example, swapped_rooms};
+	use crate::amphipod::generate::{shuffled, Rng};
+	use crate::amphipod::{easiest_moves, Plan};
+
+	fn maps() -> Vec<Map> {
+		let moved = "#############\n#.....D.....#\n###.#B#C#D###\n  #A#B#C#A#\n  #########\n";
+
+		vec![swapped_rooms(), example(), moved.parse().unwrap()]
+	}
+
+	#[test]
+	fn test_any_number_of_threads_should_find_the_cheapest_plan() {
+		for map in maps() {
+			// Given
+			let expected = easiest_moves(map.clone()).map(|plan| plan.energy);
+
+			for threads in [1, 2, 4] {
+				// When
+				let result = Parallel::new(threads).search(&map).plan.map(|plan| plan.energy);
+
+				// Then
+				assert_eq!(expected, result, "{} threads\n{}", threads, map);
+			}
+		}
+	}
+
+	#[test]
+	fn test_threads_should_agree_with_a_star_on_random_burrows() {
+		for seed in 0..10 {
+			// Given
+			let map = shuffled(3, 2, &mut Rng::new(seed)).unwrap();
+			let expected = easiest_moves(map.clone()).map(|plan| plan.energy);
+
+			// When
+			let result = Parallel::new(3).search(&map).plan.map(|plan| plan.energy);
+
+			// Then
+			assert_eq!(expected, result, "seed {}\n{}", seed, map);
+		}
+	}
+
+	#[test]
+	fn test_plan_should_add_up_to_its_energy() {
+		// Given
+		let map = maps().remove(1);
+
+		// When
+		let plan = Parallel::new(3).search(&map).plan.unwrap();
+
+		// Then
+		let maps = plan.replay(&map).unwrap();
+		assert!(maps.last().unwrap().amphipods_organized());
+		assert_eq!(plan.energy, plan.moves.iter().map(|step| step.energy).sum());
+	}
+
+	#[test]
+	fn test_organized_map_should_need_no_moves() {
+		// Given
+		let map: Map = "#########\n#.......#\n###A#B###\n  #####\n".parse().unwrap();
+
+		// When
+		let result = Parallel::new(2).search(&map).plan;
+
+		// Then
+		assert_eq!(Some(Plan { energy: 0, moves: vec![] }), result);
+	}
+
+	#[test]
+	fn test_zero_threads_should_use_one() {
+		assert_eq!(1, Parallel::new(0).threads.get());
+	}
+}
@@ -0,0 +1,472 @@
+//! Reading maps from drawings of the burrow.
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::{Amphipod, Burrow, EnergyCostError, EnergyCosts, Map, Position};
+
+/// How many times more than any plan can need a search may add up without overflowing. The
+/// searches add weighted estimates to the energy spent so far.
+const ENERGY_HEADROOM: i32 = 8;
+
+/// Reasons a drawing of a burrow could not be read as a [`Map`].
+///
+/// Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+	/// There is no open square in the drawing.
+	Empty,
+	/// The drawing is too large to have its squares numbered.
+	TooLarge,
+	/// A character that is neither wall, open square nor amphipod.
+	UnexpectedCharacter {
+		line: usize,
+		column: usize,
+		character: char,
+	},
+	/// An open square that is next to the outside instead of a wall.
+	MissingWall { line: usize, column: usize },
+	/// An open square that is neither in the hallway nor in a room.
+	StraySquare { line: usize, column: usize },
+	/// There are no rooms below the hallway.
+	NoRooms,
+	/// An amphipod starts right outside a room, where it isn't allowed to stop.
+	AmphipodOnEntrance { line: usize, column: usize },
+	/// There isn't one kind of amphipod for every room.
+	KindsDoNotMatchRooms { kinds: Vec<char>, rooms: usize },
+	/// There are not as many amphipods of a kind as there are squares in its room.
+	WrongAmphipodCount { color: char, expected: usize, found: usize },
+	/// The energy cost table after the drawing could not be parsed.
+	InvalidEnergyCosts(EnergyCostError),
+	/// There is no energy cost for a kind of amphipod.
+	MissingEnergyCost(char),
+	/// The energy costs are so high that the energy of a plan might not fit in an `i32`.
+	TooMuchEnergy,
+	/// The burrow isn't the one from the puzzle, folded to rooms two squares deep.
+	NotFolded,
+}
+
+impl fmt::Display for MapError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			MapError::Empty => write!(f, "there is no burrow in the drawing"),
+			MapError::TooLarge => write!(f, "the drawing is too large"),
+			MapError::UnexpectedCharacter { line, column, character } => {
+				write!(f, "line {}, column {}: unexpected character {:?}", line, column, character)
+			}
+			MapError::MissingWall { line, column } => {
+				write!(f, "line {}, column {}: open square without a wall", line, column)
+			}
+			MapError::StraySquare { line, column } => write!(
+				f,
+				"line {}, column {}: open square outside the hallway and the rooms",
+				line, column
+			),
+			MapError::NoRooms => write!(f, "there are no rooms below the hallway"),
+			MapError::AmphipodOnEntrance { line, column } => {
+				write!(f, "line {}, column {}: amphipod outside a room", line, column)
+			}
+			MapError::KindsDoNotMatchRooms { kinds, rooms } => write!(
+				f,
+				"{} kinds of amphipods ({}) for {} rooms",
+				kinds.len(),
+				kinds.iter().collect::<String>(),
+				rooms
+			),
+			MapError::WrongAmphipodCount { color, expected, found } => {
+				write!(f, "expected {} amphipods of kind {}, found {}", expected, color, found)
+			}
+			MapError::InvalidEnergyCosts(error) => write!(f, "{}", error),
+			MapError::MissingEnergyCost(color) => write!(f, "no energy cost for amphipod {}", color),
+			MapError::TooMuchEnergy => write!(f, "the energy costs are too high for the burrow"),
+			MapError::NotFolded => write!(f, "only the burrow from the puzzle, with rooms two squares deep, is folded"),
+		}
+	}
+}
+
+impl error::Error for MapError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			MapError::InvalidEnergyCosts(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<EnergyCostError> for MapError {
+	fn from(error: EnergyCostError) -> Self {
+		MapError::InvalidEnergyCosts(error)
+	}
+}
+
+/// Split the lines into the drawing and the energy cost table after the first blank line.
+///
+/// The costs in the table are added to the default costs.
+fn split_drawing<S: AsRef<str>>(lines: &[S]) -> Result<(&[S], EnergyCosts), EnergyCostError> {
+	let drawing_end = lines
+		.iter()
+		.position(|line| line.as_ref().trim().is_empty())
+		.unwrap_or(lines.len());
+	let (drawing, table) = lines.split_at(drawing_end);
+
+	let table = table.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join("\n");
+
+	let mut costs = EnergyCosts::default();
+	for (color, energy) in table.parse::<EnergyCosts>()?.iter() {
+		costs.insert(color, energy);
+	}
+
+	Ok((drawing, costs))
+}
+
+/// Every amphipod in the drawing, none of them has moved.
+fn amphipods<S: AsRef<str>>(drawing: &[S]) -> Vec<Amphipod> {
+	drawing
+		.iter()
+		.enumerate()
+		.flat_map(|(y, line)| {
+			line.as_ref()
+				.chars()
+				.enumerate()
+				.filter(|(_, c)| c.is_ascii_uppercase())
+				.map(move |(x, color)| Amphipod {
+					position: Position { x: x as u8, y: y as u8 },
+					color,
+					has_moved: false,
+				})
+		})
+		.collect()
+}
+
+/// Check that the drawing is a well formed burrow.
+fn validate<S: AsRef<str>>(drawing: &[S], burrow: &Burrow, amphipods: &[Amphipod]) -> Result<(), MapError> {
+	let grid: Vec<Vec<char>> = drawing.iter().map(|line| line.as_ref().chars().collect()).collect();
+	let at = |x: isize, y: isize| -> char {
+		match (usize::try_from(x), usize::try_from(y)) {
+			(Ok(x), Ok(y)) => grid.get(y).and_then(|row| row.get(x)).copied().unwrap_or(' '),
+			_ => ' ',
+		}
+	};
+
+	for (y, row) in grid.iter().enumerate() {
+		for (x, c) in row.iter().enumerate() {
+			let (line, column) = (y + 1, x + 1);
+
+			match c {
+				'#' | ' ' => continue,
+				'.' | 'A'..='Z' => {}
+				_ => return Err(MapError::UnexpectedCharacter { line, column, character: *c }),
+			}
+
+			let (x, y) = (x as isize, y as isize);
+			if [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+				.iter()
+				.any(|(x, y)| at(*x, *y) == ' ')
+			{
+				return Err(MapError::MissingWall { line, column });
+			}
+
+			let position = Position { x: x as u8, y: y as u8 };
+			if !burrow.hallway.contains(&position) && burrow.room_at(&position).is_none() {
+				return Err(MapError::StraySquare { line, column });
+			}
+		}
+	}
+
+	if burrow.hallway.is_empty() {
+		return Err(MapError::Empty);
+	}
+	if burrow.rooms.is_empty() {
+		return Err(MapError::NoRooms);
+	}
+
+	let entrances = burrow.entrances();
+	if let Some(amphipod) = amphipods.iter().find(|a| entrances.contains(&a.position)) {
+		return Err(MapError::AmphipodOnEntrance {
+			line: amphipod.position.y as usize + 1,
+			column: amphipod.position.x as usize + 1,
+		});
+	}
+
+	let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+	for amphipod in amphipods {
+		*counts.entry(amphipod.color).or_default() += 1;
+	}
+
+	if counts.len() != burrow.rooms.len() {
+		return Err(MapError::KindsDoNotMatchRooms {
+			kinds: counts.keys().copied().collect(),
+			rooms: burrow.rooms.len(),
+		});
+	}
+
+	for room in &burrow.rooms {
+		let found = counts.get(&room.color).copied().unwrap_or(0);
+		if found != room.depth() {
+			return Err(MapError::WrongAmphipodCount {
+				color: room.color,
+				expected: room.depth(),
+				found,
+			});
+		}
+
+		if burrow.energy_cost(room.color).is_none() {
+			return Err(MapError::MissingEnergyCost(room.color));
+		}
+	}
+
+	if burrow.max_energy().and_then(|energy| energy.checked_mul(ENERGY_HEADROOM)).is_none() {
+		return Err(MapError::TooMuchEnergy);
+	}
+
+	Ok(())
+}
+
+impl FromStr for Map {
+	type Err = MapError;
+
+	/// Read and check a map from a drawing of the burrow.
+	///
+	/// The drawing may be followed by a blank line and a table of [`EnergyCosts`], adding to or
+	/// replacing the default costs. The drawing must be a hallway with straight rooms below it,
+	/// surrounded by walls, with as many amphipods of each kind as there are squares in their room.
+	/// The energy costs must be low enough that the energy of a plan can't overflow.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let lines: Vec<&str> = s.trim_end().lines().collect();
+		let (drawing, costs) = split_drawing(&lines)?;
+
+		if drawing.len() > u8::MAX as usize || drawing.iter().any(|line| line.chars().count() > u8::MAX as usize) {
+			return Err(MapError::TooLarge);
+		}
+
+		let burrow = Burrow::with_costs(drawing, costs);
+		let amphipods = amphipods(drawing);
+
+		validate(drawing, &burrow, &amphipods)?;
+
+		Ok(Map::new(Arc::new(burrow), amphipods))
+	}
+}
+
+impl FromIterator<String> for Map {
+	/// Read a map from the lines of a drawing of the burrow.
+	///
+	/// The layout of the burrow is worked out from the drawing, see [`Burrow`]. The drawing may be
+	/// followed by a blank line and a table of [`EnergyCosts`], adding to or replacing the
+	/// default costs.
+	///
+	/// Unlike [`Map::from_str`] the drawing isn't checked, use that for input that might be wrong.
+	///
+	/// ## Panics
+	///
+	/// If the energy cost table can't be parsed.
+	fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
+		let lines: Vec<String> = iter.into_iter().collect();
+		let (drawing, costs) = split_drawing(&lines).expect("invalid energy costs");
+
+		let burrow = Burrow::with_costs(drawing, costs);
+
+		Map::new(Arc::new(burrow), amphipods(drawing))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_example_should_be_read() {
+		// Given
+		let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		let expected: Map = input.lines().map(String::from).collect();
+		assert_eq!(Ok(expected), result);
+	}
+
+	#[test]
+	fn test_energy_costs_after_the_drawing_should_be_used() {
+		// Given
+		let input = "#########\n#.......#\n###B#A###\n  #####\n\nA=7\n";
+
+		// When
+		let result = input.parse::<Map>().unwrap();
+
+		// Then
+		assert_eq!((Some(7), Some(10)), (result.burrow.energy_cost('A'), result.burrow.energy_cost('B')));
+	}
+
+	#[test]
+	fn test_stray_character_should_be_an_error() {
+		// Given
+		let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#a#\n  #########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(
+			Err(MapError::UnexpectedCharacter {
+				line: 4,
+				column: 10,
+				character: 'a',
+			}),
+			result
+		);
+	}
+
+	#[test]
+	fn test_hole_in_the_wall_should_be_an_error() {
+		// Given
+		let input = "#############\n#............\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(Err(MapError::MissingWall { line: 2, column: 13 }), result);
+	}
+
+	#[test]
+	fn test_square_beside_a_room_should_be_an_error() {
+		// Given
+		let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A.#\n  ##########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(Err(MapError::StraySquare { line: 4, column: 11 }), result);
+	}
+
+	#[test]
+	fn test_too_few_amphipods_should_be_an_error() {
+		// Given
+		let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#.#\n  #########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(
+			Err(MapError::WrongAmphipodCount {
+				color: 'A',
+				expected: 2,
+				found: 1,
+			}),
+			result
+		);
+	}
+
+	#[test]
+	fn test_too_many_amphipods_should_be_an_error() {
+		// Given
+		let input = "#############\n#.A.........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(
+			Err(MapError::WrongAmphipodCount {
+				color: 'A',
+				expected: 2,
+				found: 3,
+			}),
+			result
+		);
+	}
+
+	#[test]
+	fn test_kind_without_room_should_be_an_error() {
+		// Given
+		let input = "#############\n#...........#\n###B#C#B#E###\n  #A#D#C#A#\n  #########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert!(matches!(result, Err(MapError::KindsDoNotMatchRooms { rooms: 4, .. })));
+	}
+
+	#[test]
+	fn test_amphipod_on_entrance_should_be_an_error() {
+		// Given
+		let input = "#############\n#..B........#\n###.#C#B#D###\n  #A#D#C#A#\n  #########\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(Err(MapError::AmphipodOnEntrance { line: 2, column: 4 }), result);
+	}
+
+	#[test]
+	fn test_kind_without_energy_cost_should_be_an_error() {
+		// Given
+		let input = "###########\n#.........#\n###E#A#B###\n  #######\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(Err(MapError::MissingEnergyCost('E')), result);
+	}
+
+	#[test]
+	fn test_invalid_energy_costs_should_be_an_error() {
+		// Given
+		let input = "#########\n#.......#\n###B#A###\n  #####\n\nA=x\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert!(matches!(result, Err(MapError::InvalidEnergyCosts(_))));
+	}
+
+	#[test]
+	fn test_energy_costs_that_could_overflow_should_be_an_error() {
+		// Given
+		let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n\nD=1000000000\n";
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(Err(MapError::TooMuchEnergy), result);
+	}
+
+	#[test]
+	fn test_burrow_too_deep_to_pack_in_a_number_should_be_read() {
+		// Given
+		let room = "  #A#B#C#D#\n";
+		let input = format!("#############\n#...........#\n###B#A#C#D###\n{}  #########\n", room.repeat(11));
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		let map = result.unwrap();
+		assert_eq!((12, false), (map.burrow.rooms[0].depth(), crate::amphipod::state::fits(&map.burrow)));
+		assert_eq!(Some(46), crate::amphipod::easiest_moves(map).map(|plan| plan.energy));
+	}
+
+	#[test]
+	fn test_drawing_too_wide_to_number_should_be_an_error() {
+		// Given
+		let input = format!("{}\n", "#".repeat(300));
+
+		// When
+		let result = input.parse::<Map>();
+
+		// Then
+		assert_eq!(Err(MapError::TooLarge), result);
+	}
+}
@@ -0,0 +1,365 @@
+//! Playing the amphipod puzzle by hand, one move at a time.
+//!
+//! A [`Game`] only allows the moves the search makes, see [`Amphipod::possible_moves`]: out of a
+//! room to a stop in the hallway, or all the way down into the amphipod's own room. It keeps the
+//! moves made so they can be undone and redone, and asks the solver how cheaply the amphipods can
+//! still be organized from where they are.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::search::SearchStrategy;
+use super::validate::{check_step, Rule, Step};
+use super::{AStar, Amphipod, Map, Move, Plan, Position};
+
+/// A move that can't be made in the game.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum PlayError {
+	/// There is no amphipod to move on the square.
+	NoAmphipod(Position),
+	/// The amphipod can't move to the square. The rule it would break, if it breaks any of the
+	/// rules of the puzzle rather than just the stricter moves of the game.
+	Illegal { from: Position, to: Position, rule: Option<Rule> },
+}
+
+impl fmt::Display for PlayError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		match self {
+			PlayError::NoAmphipod(at) => write!(f, "there is no amphipod at ({}, {})", at.x, at.y),
+			PlayError::Illegal { from, to, rule } => {
+				write!(f, "({}, {}) -> ({}, {}) ", from.x, from.y, to.x, to.y)?;
+				match rule {
+					Some(rule) => write!(f, "{}", rule),
+					None => write!(f, "has to go all the way down into the room"),
+				}
+			}
+		}
+	}
+}
+
+impl error::Error for PlayError {}
+
+/// A game of moving the amphipods by hand.
+#[derive(Clone, Debug)]
+pub struct Game {
+	start: Map,
+	/// The moves made, together with the map after each of them.
+	made: Vec<(Move, Map)>,
+	/// The moves undone, the last one undone last.
+	undone: Vec<(Move, Map)>,
+}
+
+impl Game {
+	pub fn new(start: Map) -> Self {
+		Game {
+			start,
+			made: vec![],
+			undone: vec![],
+		}
+	}
+
+	/// The map as it is after the moves made so far.
+	pub fn map(&self) -> &Map {
+		self.made.last().map_or(&self.start, |(_, map)| map)
+	}
+
+	/// The moves made so far, in order.
+	pub fn moves(&self) -> impl Iterator<Item = &Move> {
+		self.made.iter().map(|(step, _)| step)
+	}
+
+	/// The energy spent on the moves made so far.
+	pub fn energy(&self) -> i32 {
+		self.moves().map(|step| step.energy).sum()
+	}
+
+	/// The amphipod on the square and the squares it can move to.
+	pub fn targets(&self, from: Position) -> Result<(&Amphipod, Vec<Position>), PlayError> {
+		let map = self.map();
+		let amphipod = map.occupant(&from).ok_or(PlayError::NoAmphipod(from))?;
+
+		Ok((amphipod, amphipod.possible_moves(map)))
+	}
+
+	/// Move the amphipod on `from` to `to`, returning the move made.
+	///
+	/// This forgets the moves that were undone.
+	pub fn play(&mut self, from: Position, to: Position) -> Result<Move, PlayError> {
+		let (amphipod, targets) = self.targets(from)?;
+		let step = Step {
+			color: Some(amphipod.color),
+			from,
+			to,
+			energy: None,
+		};
+
+		let map = self.map();
+		let (_, step) = match targets.contains(&to) {
+			true => check_step(map, &step).expect("the moves of the game follow the rules"),
+			false => {
+				let rule = check_step(map, &step).err();
+				return Err(PlayError::Illegal { from, to, rule });
+			}
+		};
+
+		let next = map.apply(&step).expect("the amphipod to move is on the map");
+		self.made.push((step, next));
+		self.undone.clear();
+
+		Ok(step)
+	}
+
+	/// Take back the last move made, returning it.
+	pub fn undo(&mut self) -> Option<Move> {
+		let made = self.made.pop()?;
+		let step = made.0;
+		self.undone.push(made);
+
+		Some(step)
+	}
+
+	/// Make the last move undone again, returning it.
+	pub fn redo(&mut self) -> Option<Move> {
+		let undone = self.undone.pop()?;
+		let step = undone.0;
+		self.made.push(undone);
+
+		Some(step)
+	}
+
+	/// Whether the amphipods are organized.
+	pub fn is_won(&self) -> bool {
+		self.map().amphipods_organized()
+	}
+
+	/// The cheapest way to organize the amphipods from where they are now, or `None` if the moves
+	/// made so far have made it impossible.
+	pub fn hint(&self) -> Option<Plan> {
+		AStar.search(self.map()).plan
+	}
+}
+
+/// Something the player wants to do.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Command {
+	/// Pick the amphipod on the square, or move the picked amphipod there.
+	Square(Position),
+	Undo,
+	Redo,
+	Hint,
+	Help,
+	Quit,
+}
+
+/// A line that could not be read as a [`Command`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandError(pub String);
+
+impl fmt::Display for CommandError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "unknown command {:?}, type help for the commands", self.0)
+	}
+}
+
+impl error::Error for CommandError {}
+
+impl FromStr for Command {
+	type Err = CommandError;
+
+	/// Read a command, either a word or a square written as `x y`, `x, y` or `(x, y)`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		let command = match s.to_lowercase().as_str() {
+			"u" | "undo" => Command::Undo,
+			"r" | "redo" => Command::Redo,
+			"h" | "hint" => Command::Hint,
+			"?" | "help" => Command::Help,
+			"q" | "quit" => Command::Quit,
+			square => {
+				let coordinates: Vec<u8> = square
+					.trim_start_matches('(')
+					.trim_end_matches(')')
+					.split([',', ' '])
+					.filter(|coordinate| !coordinate.is_empty())
+					.map(|coordinate| coordinate.parse().map_err(|_| CommandError(s.to_string())))
+					.collect::<Result<_, _>>()?;
+
+				match coordinates[..] {
+					[x, y] => Command::Square(Position { x, y }),
+					_ => return Err(CommandError(s.to_string())),
+				}
+			}
+		};
+
+		Ok(command)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::fixtures::swapped_rooms;
+
+	fn at(x: u8, y: u8) -> Position {
+		Position { x, y }
+	}
+
+	#[test]
+	fn test_legal_moves_should_add_up_the_energy() {
+		// Given
+		let mut game = Game::new(swapped_rooms());
+
+		// When
+		game.play(at(5, 2), at(6, 1)).unwrap();
+		game.play(at(3, 2), at(4, 1)).unwrap();
+
+		// Then
+		assert_eq!(2 + 20, game.energy());
+		assert_eq!(Some('B'), game.map().occupant(&at(4, 1)).map(|amphipod| amphipod.color));
+	}
+
+	#[test]
+	fn test_stopping_outside_a_room_should_be_refused_with_the_rule() {
+		// Given
+		let mut game = Game::new(swapped_rooms());
+
+		// When
+		let result = game.play(at(3, 2), at(3, 1));
+
+		// Then
+		let expected = PlayError::Illegal {
+			from: at(3, 2),
+			to: at(3, 1),
+			rule: Some(Rule::NotAStop),
+		};
+		assert_eq!(Err(expected), result);
+		assert_eq!(0, game.moves().count());
+	}
+
+	#[test]
+	fn test_stopping_halfway_into_a_room_should_be_refused() {
+		// Given
+		let map = "#############\n#...B.B.....#\n###A#.#C#D###\n  #A#.#C#D#\n  #########\n".parse().unwrap();
+		let mut game = Game::new(map);
+
+		// When
+		let result = game.play(at(4, 1), at(5, 2));
+
+		// Then
+		let expected = PlayError::Illegal {
+			from: at(4, 1),
+			to: at(5, 2),
+			rule: None,
+		};
+		assert_eq!(Err(expected), result);
+		assert_eq!(vec![at(5, 3)], game.targets(at(4, 1)).unwrap().1);
+	}
+
+	#[test]
+	fn test_empty_square_should_have_no_amphipod_to_move() {
+		// Given
+		let mut game = Game::new(swapped_rooms());
+
+		// When
+		let result = game.play(at(1, 1), at(2, 1));
+
+		// Then
+		assert_eq!(Err(PlayError::NoAmphipod(at(1, 1))), result);
+	}
+
+	#[test]
+	fn test_undo_and_redo_should_restore_the_moves() {
+		// Given
+		let mut game = Game::new(swapped_rooms());
+		let first = game.play(at(5, 2), at(6, 1)).unwrap();
+		let second = game.play(at(3, 2), at(4, 1)).unwrap();
+
+		// When
+		let undone = (game.undo(), game.undo(), game.undo());
+		let at_start = (game.energy(), game.map() == &swapped_rooms());
+		let redone = (game.redo(), game.redo(), game.redo());
+
+		// Then
+		assert_eq!((Some(second), Some(first), None), undone);
+		assert_eq!((0, true), at_start);
+		assert_eq!((Some(first), Some(second), None), redone);
+		assert_eq!(22, game.energy());
+	}
+
+	#[test]
+	fn test_new_move_should_forget_undone_moves() {
+		// Given
+		let mut game = Game::new(swapped_rooms());
+		game.play(at(5, 2), at(4, 1)).unwrap();
+		game.undo();
+
+		// When
+		game.play(at(5, 2), at(6, 1)).unwrap();
+
+		// Then
+		assert_eq!(None, game.redo());
+	}
+
+	#[test]
+	fn test_hint_should_add_up_to_the_cheapest_plan() {
+		// Given
+		let mut game = Game::new(swapped_rooms());
+		let first = game.hint().unwrap().moves[0];
+		game.play(first.from, first.to).unwrap();
+
+		// When
+		let result = game.hint().map(|plan| game.energy() + plan.energy);
+
+		// Then
+		assert_eq!(Some(46), result);
+	}
+
+	#[test]
+	fn test_hint_should_show_an_expensive_opening() {
+		// Given
+		let mut game = Game::new(swapped_rooms());
+		game.play(at(5, 2), at(4, 1)).unwrap();
+
+		// When
+		let result = game.hint().map(|plan| game.energy() + plan.energy);
+
+		// Then
+		assert!(result.is_some_and(|energy| energy > 46), "{:?}", result);
+	}
+
+	#[test]
+	fn test_finishing_the_plan_should_win() {
+		// Given
+		let start = swapped_rooms();
+		let mut game = Game::new(start.clone());
+
+		// When
+		for step in AStar.search(&start).plan.unwrap().moves {
+			game.play(step.from, step.to).unwrap();
+		}
+
+		// Then
+		assert_eq!((true, 46), (game.is_won(), game.energy()));
+	}
+
+	#[test]
+	fn test_commands_should_be_parsed() {
+		// Given
+		let lines = ["3 2", "(3, 2)", "3,2", " Undo ", "r", "hint", "?", "q"];
+
+		// When
+		let result: Vec<Command> = lines.iter().map(|line| line.parse().unwrap()).collect();
+
+		// Then
+		let square = Command::Square(at(3, 2));
+		let words = [Command::Undo, Command::Redo, Command::Hint, Command::Help, Command::Quit];
+		assert_eq!([[square; 3].as_slice(), &words].concat(), result);
+	}
+
+	#[test]
+	fn test_garbled_command_should_be_an_error() {
+		assert_eq!(Err(CommandError("3 x".to_string())), "3 x".parse::<Command>());
+		assert_eq!(Err(CommandError("1 2 3".to_string())), "1 2 3".parse::<Command>());
+	}
+}
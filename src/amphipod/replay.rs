@@ -0,0 +1,291 @@
+//! Replaying a plan as an animation in the terminal.
+//!
+//! The amphipods walk one square per frame, and each frame is drawn over the previous one with
+//! ANSI escapes. Every kind of amphipod gets a colour of its own, and the one that is moving is
+//! highlighted. Without colours there are no escapes at all: the frames are written one after the
+//! other, and the moving amphipod is drawn in lowercase instead.
+
+use std::cmp::Ordering;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use super::{Map, Move, Plan, Position};
+
+/// The colours of the kinds of amphipods, in the order of their rooms.
+const COLORS: [&str; 6] = ["31", "32", "33", "34", "35", "36"];
+
+/// Bold and reversed, for the moving amphipod.
+const HIGHLIGHT: &str = "1;7";
+
+/// The burrow at one point of a replay.
+#[derive(Clone, Debug)]
+pub struct Frame {
+	pub map: Map,
+	/// The move being made and its number, counted from 1.
+	pub step: Option<(usize, Move)>,
+	/// Where the moving amphipod is now.
+	pub moving: Option<Position>,
+	/// The energy spent so far.
+	pub energy: i32,
+}
+
+impl Frame {
+	/// The burrow and a line telling the move and the energy spent so far.
+	pub fn draw(&self, color: bool) -> String {
+		let burrow = &self.map.burrow;
+		let mut rows: Vec<Vec<String>> = burrow
+			.drawing
+			.iter()
+			.map(|row| row.iter().map(char::to_string).collect())
+			.collect();
+
+		for amphipod in &self.map.amphipods {
+			let position = amphipod.position;
+			let moving = self.moving == Some(position);
+			let room = burrow.rooms.iter().position(|room| room.color == amphipod.color).unwrap_or(0);
+			let kind = COLORS[room % COLORS.len()];
+
+			rows[position.y as usize][position.x as usize] = match (color, moving) {
+				(true, true) => format!("\x1b[{};{}m{}\x1b[0m", kind, HIGHLIGHT, amphipod.color),
+				(true, false) => format!("\x1b[{}m{}\x1b[0m", kind, amphipod.color),
+				(false, true) => amphipod.color.to_ascii_lowercase().to_string(),
+				(false, false) => amphipod.color.to_string(),
+			};
+		}
+
+		let mut drawing: String = rows.into_iter().map(|row| row.concat() + "\n").collect();
+		match &self.step {
+			Some((number, step)) => drawing += &format!("move {}: {}\n", number, step),
+			None => drawing += "start\n",
+		}
+		drawing += &format!("energy {}\n", self.energy);
+
+		drawing
+	}
+}
+
+/// The squares an amphipod walks through making the move, in the order it walks them: up out of
+/// its room, along the hallway and down into the other room.
+///
+/// [`Map::path`] passes the same squares, but not in that order.
+fn walk(map: &Map, step: &Move) -> Vec<Position> {
+	let hallway_y = map.burrow.hallway_y;
+	let mut square = step.from;
+	let mut squares = vec![];
+
+	while square != step.to {
+		square = match (square.x.cmp(&step.to.x), square.y.cmp(&step.to.y)) {
+			(Ordering::Equal, Ordering::Less) => Position { y: square.y + 1, ..square },
+			(Ordering::Equal, _) => Position { y: square.y - 1, ..square },
+			_ if square.y > hallway_y => Position { y: square.y - 1, ..square },
+			(Ordering::Less, _) => Position { x: square.x + 1, ..square },
+			(Ordering::Greater, _) => Position { x: square.x - 1, ..square },
+		};
+		squares.push(square);
+	}
+
+	squares
+}
+
+/// The frames of making the moves of the plan from `start`, one square at a time.
+///
+/// Returns `None` if a move can't be made in the map it is applied to.
+pub fn frames(start: &Map, plan: &Plan) -> Option<Vec<Frame>> {
+	let mut frames = vec![Frame {
+		map: start.clone(),
+		step: None,
+		moving: None,
+		energy: 0,
+	}];
+	let mut map = start.clone();
+	let mut energy = 0;
+
+	for (i, step) in plan.moves.iter().enumerate() {
+		let amphipod = map.occupant(&step.from)?;
+		let cost = amphipod.energy_cost(&map.burrow);
+
+		for square in walk(&map, step) {
+			energy += cost;
+			let walked = Move {
+				to: square,
+				..*step
+			};
+
+			frames.push(Frame {
+				map: map.apply(&walked)?,
+				step: Some((i + 1, *step)),
+				moving: Some(square),
+				energy,
+			});
+		}
+
+		map = map.apply(step)?;
+	}
+
+	Some(frames)
+}
+
+/// Draw the frames one after the other, waiting `delay` between them. With colours each frame is
+/// drawn over the previous one, otherwise below it after an empty line.
+pub fn play(out: &mut impl Write, frames: &[Frame], color: bool, delay: Duration) -> io::Result<()> {
+	let mut height = 0;
+
+	for (i, frame) in frames.iter().enumerate() {
+		if i > 0 {
+			thread::sleep(delay);
+			match color {
+				// Up to the first line of the previous frame, and clear everything below it.
+				true => write!(out, "\x1b[{}A\x1b[J", height)?,
+				false => writeln!(out)?,
+			}
+		}
+
+		let drawing = frame.draw(color);
+		height = drawing.lines().count();
+		write!(out, "{}", drawing)?;
+		out.flush()?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::easiest_moves;
+	use crate::amphipod::fixtures::{example, swapped_rooms};
+
+	#[test]
+	fn test_frames_should_walk_every_square_of_the_plan() {
+		// Given
+		let map = swapped_rooms();
+		let plan = easiest_moves(map.clone()).unwrap();
+
+		// When
+		let frames = frames(&map, &plan).unwrap();
+
+		// Then
+		let squares: usize = plan.moves.iter().map(|step| map.path(&step.from, &step.to).len()).sum();
+		let last = frames.last().unwrap();
+		assert_eq!(1 + squares, frames.len());
+		assert_eq!((plan.energy, true), (last.energy, last.map.amphipods_organized()));
+	}
+
+	#[test]
+	fn test_moving_amphipod_should_walk_to_the_next_square() {
+		// Given
+		let map = example();
+		let plan = easiest_moves(map.clone()).unwrap();
+
+		// When
+		let frames = frames(&map, &plan).unwrap();
+
+		// Then
+		for pair in frames.windows(2).filter(|pair| pair[0].step.map(|(i, _)| i) == pair[1].step.map(|(i, _)| i)) {
+			let (from, to) = (pair[0].moving.unwrap(), pair[1].moving.unwrap());
+			assert_eq!(1, from.x.abs_diff(to.x) + from.y.abs_diff(to.y), "{:?} -> {:?}", from, to);
+		}
+	}
+
+	#[test]
+	fn test_energy_should_never_go_down() {
+		// Given
+		let map = swapped_rooms();
+		let plan = easiest_moves(map.clone()).unwrap();
+
+		// When
+		let frames = frames(&map, &plan).unwrap();
+
+		// Then
+		assert!(frames.windows(2).all(|pair| pair[0].energy < pair[1].energy));
+	}
+
+	#[test]
+	fn test_plan_that_cant_be_made_should_have_no_frames() {
+		// Given
+		let map = swapped_rooms();
+		let step = Move {
+			color: 'A',
+			from: Position { x: 1, y: 1 },
+			to: Position { x: 2, y: 1 },
+			energy: 1,
+		};
+		let plan = Plan { energy: 1, moves: vec![step] };
+
+		// When
+		let result = frames(&map, &plan);
+
+		// Then
+		assert!(result.is_none());
+	}
+
+	#[test]
+	fn test_frame_without_color_should_lowercase_the_moving_amphipod() {
+		// Given
+		let map = swapped_rooms();
+		let plan = easiest_moves(map.clone()).unwrap();
+		let frame = &frames(&map, &plan).unwrap()[1];
+
+		// When
+		let result = frame.draw(false);
+
+		// Then
+		let step = plan.moves[0];
+		let (x, y) = frame.moving.map(|square| (square.x as usize, square.y as usize)).unwrap();
+		let mut rows: Vec<Vec<char>> = result.lines().map(|row| row.chars().collect()).collect();
+		assert!(!result.contains('\x1b'));
+		assert_eq!(step.color.to_ascii_lowercase(), rows[y][x]);
+
+		rows[y][x] = step.color;
+		let others: String = rows.iter().map(|row| row.iter().collect::<String>() + "\n").collect();
+		assert_eq!(format!("{}move 1: {}\nenergy {}\n", frame.map, step, frame.energy), others);
+	}
+
+	#[test]
+	fn test_frame_with_color_should_color_every_amphipod() {
+		// Given
+		let map = swapped_rooms();
+
+		// When
+		let result = frames(&map, &Plan { energy: 0, moves: vec![] }).unwrap()[0].draw(true);
+
+		// Then
+		assert_eq!(map.amphipods.len(), result.matches("\x1b[0m").count());
+		assert!(result.contains("\x1b[31mA\x1b[0m"));
+		assert!(!result.contains(HIGHLIGHT));
+	}
+
+	#[test]
+	fn test_play_should_draw_each_frame_over_the_last() {
+		// Given
+		let map = swapped_rooms();
+		let plan = easiest_moves(map.clone()).unwrap();
+		let frames = frames(&map, &plan).unwrap();
+		let mut out = vec![];
+
+		// When
+		play(&mut out, &frames, true, Duration::ZERO).unwrap();
+
+		// Then
+		let out = String::from_utf8(out).unwrap();
+		assert_eq!(frames.len() - 1, out.matches("\x1b[7A\x1b[J").count());
+		assert!(out.ends_with(&frames.last().unwrap().draw(true)));
+	}
+
+	#[test]
+	fn test_play_without_color_should_write_plain_frames() {
+		// Given
+		let map = swapped_rooms();
+		let plan = easiest_moves(map.clone()).unwrap();
+		let frames = frames(&map, &plan).unwrap();
+		let mut out = vec![];
+
+		// When
+		play(&mut out, &frames, false, Duration::ZERO).unwrap();
+
+		// Then
+		let drawn: Vec<String> = frames.iter().map(|frame| frame.draw(false)).collect();
+		assert_eq!(drawn.join("\n"), String::from_utf8(out).unwrap());
+	}
+}
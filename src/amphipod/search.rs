@@ -0,0 +1,763 @@
+//! Different ways of searching for the cheapest way to organize the amphipods.
+//!
+//! Every strategy explores the same states, through [`Map::successors`], so they can be compared
+//! by the [`SearchStats`] they return with the plan.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use super::frontier::{BucketQueue, Frontier};
+use super::{heuristic, Map, Move, Parallel, Plan, State};
+
+/// The names of the strategies, as accepted by [`strategy`].
+pub const STRATEGIES: &[&str] = &["dijkstra", "astar", "ida", "dfs", "parallel", "anytime"];
+
+/// How many maps are expanded between each report of the progress of a search.
+pub const PROGRESS_INTERVAL: usize = 1 << 16;
+
+/// The strategy with the given name, if there is one.
+pub fn strategy(name: &str) -> Option<Box<dyn SearchStrategy>> {
+	match name {
+		"dijkstra" => Some(Box::new(Dijkstra)),
+		"astar" => Some(Box::new(AStar)),
+		"ida" => Some(Box::new(IdaStar)),
+		"dfs" => Some(Box::new(MemoizedDfs)),
+		"parallel" => Some(Box::new(Parallel::default())),
+		"anytime" => Some(Box::new(Anytime::default())),
+		_ => None,
+	}
+}
+
+/// How much work a search has done.
+///
+/// Depth first searches have no queue, for them the maps pushed are the maps they have gone down
+/// into, and the peak queue length is the deepest they have gone.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct SearchStats {
+	/// The number of maps whose successors were looked at.
+	pub expanded: usize,
+	/// The number of maps put in the queue.
+	pub pushed: usize,
+	/// The largest number of maps in the queue at the same time.
+	pub peak_queue: usize,
+	/// The largest number of maps remembered at the same time.
+	pub visited: usize,
+	/// The number of times a map was skipped because it had already been reached as cheaply.
+	pub duplicates: usize,
+	/// The number of times the amphipods were found organized.
+	pub goal_hits: usize,
+	/// The number of deadlocked maps that weren't searched.
+	pub pruned: usize,
+	/// The energy of the cheapest plan found so far.
+	pub best: Option<i32>,
+	/// How long the search has taken.
+	pub elapsed: Duration,
+}
+
+impl fmt::Display for SearchStats {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(
+			f,
+			"expanded {} maps, pushed {}, peak queue {}, visited {}, skipped {} duplicates, \
+			 {} goal hits, pruned {} deadlocked maps, in {:.3?}",
+			self.expanded,
+			self.pushed,
+			self.peak_queue,
+			self.visited,
+			self.duplicates,
+			self.goal_hits,
+			self.pruned,
+			self.elapsed
+		)?;
+		if let Some(best) = self.best {
+			write!(f, ", cheapest plan {}", best)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// The result of a search.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Search {
+	/// The cheapest plan, or `None` if the amphipods can't be organized.
+	pub plan: Option<Plan>,
+	/// Whether the plan is proven to be the cheapest, or that there is none. This is only false
+	/// when the search ran out of time.
+	pub optimal: bool,
+	pub stats: SearchStats,
+}
+
+/// Called with the statistics so far, every [`PROGRESS_INTERVAL`] expanded maps and whenever a
+/// cheaper plan is found.
+pub type Progress<'a> = &'a mut (dyn FnMut(&SearchStats) + Send);
+
+/// Something that happened to a map during a best first search.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Event {
+	/// The map was reached with `cost`, through a move from another map unless it's the start.
+	/// It's estimated to need `estimate` more to organize.
+	Reached {
+		state: State,
+		from: Option<(State, Move)>,
+		cost: i32,
+		estimate: i32,
+	},
+	/// The map was taken out of the queue, and the maps it leads to are looked at.
+	Expanded { state: State },
+}
+
+/// Called with every [`Event`] of a search, as it happens.
+pub type Observer<'a> = &'a mut dyn FnMut(Event);
+
+/// A way of searching for the cheapest plan.
+pub trait SearchStrategy {
+	/// Find the cheapest way to organize the amphipods, starting from `start`, and report the
+	/// progress to `progress`.
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search;
+
+	/// Find the cheapest way to organize the amphipods, starting from `start`.
+	fn search(&self, start: &Map) -> Search {
+		self.search_with_progress(start, &mut |_| {})
+	}
+}
+
+/// Keeps the statistics of a search and reports its progress.
+struct Tracker<'a> {
+	stats: SearchStats,
+	started: Instant,
+	progress: Progress<'a>,
+}
+
+impl<'a> Tracker<'a> {
+	fn new(progress: Progress<'a>) -> Self {
+		Tracker {
+			stats: SearchStats::default(),
+			started: Instant::now(),
+			progress,
+		}
+	}
+
+	/// Count an expanded map, and report the progress if it's time to.
+	fn expand(&mut self) {
+		self.stats.expanded += 1;
+
+		if self.stats.expanded.is_multiple_of(PROGRESS_INTERVAL) {
+			self.stats.elapsed = self.started.elapsed();
+			(self.progress)(&self.stats);
+		}
+	}
+
+	/// Count a map put in the queue, which now holds `queued` maps.
+	fn push(&mut self, queued: usize) {
+		self.stats.pushed += 1;
+		self.stats.peak_queue = self.stats.peak_queue.max(queued);
+	}
+
+	/// Note that `visited` maps are remembered.
+	fn visit(&mut self, visited: usize) {
+		self.stats.visited = self.stats.visited.max(visited);
+	}
+
+	/// Note that a plan needing `energy` was found, and report it if it's cheaper than before.
+	fn improve(&mut self, energy: i32) {
+		if self.stats.best.is_none_or(|best| energy < best) {
+			self.stats.best = Some(energy);
+			self.stats.elapsed = self.started.elapsed();
+			(self.progress)(&self.stats);
+		}
+	}
+
+	/// The result of the search.
+	fn finish(mut self, plan: Option<Plan>, optimal: bool) -> Search {
+		self.stats.elapsed = self.started.elapsed();
+
+		Search {
+			plan,
+			optimal,
+			stats: self.stats,
+		}
+	}
+}
+
+/// The maps waiting in a best first search, with the cost they were reached with.
+pub type Queued = (State, i32);
+
+/// Uniform cost search, always expanding the cheapest map reached so far.
+pub struct Dijkstra;
+
+impl SearchStrategy for Dijkstra {
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search {
+		<BestFirst>::dijkstra().search_with_progress(start, progress)
+	}
+}
+
+/// Best first search, expanding the map with the lowest estimated total cost first.
+pub struct AStar;
+
+impl SearchStrategy for AStar {
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search {
+		<BestFirst>::a_star().search_with_progress(start, progress)
+	}
+}
+
+/// Best first search with the maps waiting in a `F`, a [`BucketQueue`] unless told otherwise.
+///
+/// [`Dijkstra`] and [`AStar`] are searches with the default frontier. This is for trying them with
+/// other frontiers, and for watching what they do with [`BestFirst::search_observed`].
+pub struct BestFirst<F = BucketQueue<Queued>> {
+	estimate: fn(&Map) -> i32,
+	frontier: PhantomData<F>,
+}
+
+impl<F> BestFirst<F> {
+	/// Uniform cost search, like [`Dijkstra`].
+	pub fn dijkstra() -> Self {
+		BestFirst {
+			estimate: |_| 0,
+			frontier: PhantomData,
+		}
+	}
+
+	/// Search guided by [`heuristic`], like [`AStar`].
+	pub fn a_star() -> Self {
+		BestFirst {
+			estimate: heuristic,
+			frontier: PhantomData,
+		}
+	}
+}
+
+impl<F: Frontier<Queued>> BestFirst<F> {
+	/// Search like [`SearchStrategy::search_with_progress`], telling `observer` about every map
+	/// reached and expanded.
+	pub fn search_observed(&self, start: &Map, progress: Progress, observer: Observer) -> Search {
+		let settings = Settings {
+			estimate: self.estimate,
+			weight: 1,
+			deadline: None,
+		};
+
+		best_first::<F>(start, settings, Tracker::new(progress), observer)
+	}
+}
+
+impl<F: Frontier<Queued>> SearchStrategy for BestFirst<F> {
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search {
+		self.search_observed(start, progress, &mut |_| {})
+	}
+}
+
+/// How a best first search orders the maps, and when it stops.
+#[derive(Clone, Copy)]
+struct Settings {
+	estimate: fn(&Map) -> i32,
+	/// How many times more the estimate weighs than the cost so far when ordering the maps.
+	weight: i32,
+	/// When to give up looking for a cheaper plan.
+	deadline: Option<Instant>,
+}
+
+/// The cheapest known cost of a map, and the map and move it was reached from.
+pub(super) type CameFrom = HashMap<State, (i32, Option<(State, Move)>)>;
+
+/// The plan reaching `goal`, following the moves the maps were reached with.
+pub(super) fn plan_to(came_from: &CameFrom, goal: &State) -> Plan {
+	let mut moves = vec![];
+	let mut current = goal.clone();
+	while let Some((_, Some((previous, step)))) = came_from.get(&current) {
+		moves.push(*step);
+		current = previous.clone();
+	}
+	moves.reverse();
+
+	Plan {
+		energy: moves.iter().map(|step| step.energy).sum(),
+		moves,
+	}
+}
+
+/// Search the maps in order of `cost + weight * estimate(map)`.
+///
+/// The maps are kept packed, and only unpacked when they are expanded. Unless the estimate is
+/// weighted, nothing left in the queue can lead to a cheaper plan once an organized map comes out
+/// of it, so the search stops there. A weighted search goes on looking for cheaper plans until
+/// the queue is empty or the deadline has passed.
+fn best_first<F: Frontier<Queued>>(
+	start: &Map,
+	settings: Settings,
+	mut tracker: Tracker,
+	observer: Observer,
+) -> Search {
+	let burrow = &start.burrow;
+	let estimate = settings.estimate;
+
+	let mut queue = F::default();
+	queue.push((start.pack(), 0), settings.weight * estimate(start));
+	tracker.push(queue.len());
+	observer(Event::Reached {
+		state: start.pack(),
+		from: None,
+		cost: 0,
+		estimate: estimate(start),
+	});
+
+	let mut came_from = CameFrom::new();
+	came_from.insert(start.pack(), (0, None));
+	tracker.visit(came_from.len());
+
+	let mut best: Option<Plan> = None;
+
+	while let Some(((state, cost), _priority)) = queue.pop() {
+		if settings.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+			return tracker.finish(best, false);
+		}
+
+		// A cheaper way to the map may have been found since it was queued.
+		if came_from.get(&state).is_some_and(|(known_cost, _)| *known_cost < cost) {
+			tracker.stats.duplicates += 1;
+			continue;
+		}
+
+		let map = state.unpack(burrow);
+		let bound = best.as_ref().map_or(i32::MAX, |plan| plan.energy);
+		if cost + estimate(&map) >= bound {
+			continue;
+		}
+
+		if map.amphipods_organized() {
+			tracker.stats.goal_hits += 1;
+			tracker.improve(cost);
+			best = Some(plan_to(&came_from, &state));
+
+			if settings.weight == 1 {
+				return tracker.finish(best, true);
+			}
+			continue;
+		}
+		tracker.expand();
+		observer(Event::Expanded { state: state.clone() });
+
+		for (step, next) in map.successors() {
+			if next.is_deadlocked() {
+				tracker.stats.pruned += 1;
+				continue;
+			}
+
+			let next_cost = cost + step.energy;
+			let next_estimate = estimate(&next);
+			if next_cost + next_estimate >= bound {
+				continue;
+			}
+
+			let next = next.pack();
+			if came_from.get(&next).is_some_and(|(known_cost, _)| next_cost >= *known_cost) {
+				tracker.stats.duplicates += 1;
+				continue;
+			}
+
+			came_from.insert(next.clone(), (next_cost, Some((state.clone(), step))));
+			tracker.visit(came_from.len());
+			observer(Event::Reached {
+				state: next.clone(),
+				from: Some((state.clone(), step)),
+				cost: next_cost,
+				estimate: next_estimate,
+			});
+			queue.push((next, next_cost), next_cost + settings.weight * next_estimate);
+			tracker.push(queue.len());
+		}
+	}
+
+	tracker.finish(best, true)
+}
+
+/// Weighted A*, that finds a plan quickly and then keeps looking for cheaper ones until there
+/// can't be any, or it runs out of time.
+///
+/// The estimate weighs `weight` times as much as the cost so far, so the search heads for
+/// organized maps before it has looked at every cheaper map. Each cheaper plan is reported as
+/// progress as soon as it's found. Maps that can't lead to anything cheaper than the best plan so
+/// far are skipped.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Anytime {
+	pub budget: Duration,
+	pub weight: i32,
+}
+
+impl Anytime {
+	/// Search for at most `budget`, with the estimate weighing three times as much as the cost.
+	pub fn new(budget: Duration) -> Self {
+		Anytime { budget, weight: 3 }
+	}
+}
+
+impl Default for Anytime {
+	/// Search for at most a second.
+	fn default() -> Self {
+		Anytime::new(Duration::from_secs(1))
+	}
+}
+
+impl SearchStrategy for Anytime {
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search {
+		let tracker = Tracker::new(progress);
+		let settings = Settings {
+			estimate: heuristic,
+			weight: self.weight,
+			deadline: Some(tracker.started + self.budget),
+		};
+
+		best_first::<BucketQueue<Queued>>(start, settings, tracker, &mut |_| {})
+	}
+}
+
+/// Iterative deepening A*, searching depth first below a growing limit on the estimated total
+/// cost.
+///
+/// Only the maps of the current iteration are remembered, and only to avoid searching below the
+/// same map twice. Energies are so varied that raising the limit to the next estimate above it
+/// would take hundreds of iterations, so the limit is raised by at least a sixteenth each time.
+/// The limit may then be above the cheapest plan, so once a plan is found the limit is lowered
+/// below its energy and the iteration goes on looking for a cheaper one.
+pub struct IdaStar;
+
+/// The state of one iteration of [`IdaStar`].
+struct Iteration<'a> {
+	limit: i32,
+	/// The cheapest cost each map has been reached with in this iteration.
+	reached: HashMap<State, i32>,
+	/// The moves leading to the map being searched.
+	moves: Vec<Move>,
+	/// The cheapest plan found so far.
+	best: Option<Plan>,
+	tracker: Tracker<'a>,
+}
+
+impl Iteration<'_> {
+	/// Search below `map`, reached with `cost`.
+	///
+	/// Returns the lowest estimated total cost that was above the limit, if any.
+	fn search(&mut self, map: &Map, cost: i32) -> Option<i32> {
+		let estimate = cost + heuristic(map);
+		if estimate > self.limit {
+			return Some(estimate);
+		}
+		if map.amphipods_organized() {
+			self.tracker.stats.goal_hits += 1;
+			self.best = Some(Plan {
+				energy: cost,
+				moves: self.moves.clone(),
+			});
+			self.limit = cost - 1;
+			return None;
+		}
+		let state = map.pack();
+		if self.reached.get(&state).is_some_and(|known| *known <= cost) {
+			self.tracker.stats.duplicates += 1;
+			return None;
+		}
+		self.reached.insert(state, cost);
+		self.tracker.visit(self.reached.len());
+		self.tracker.expand();
+
+		let mut next_limit: Option<i32> = None;
+		for (step, next) in map.successors() {
+			if next.is_deadlocked() {
+				self.tracker.stats.pruned += 1;
+				continue;
+			}
+
+			self.moves.push(step);
+			self.tracker.push(self.moves.len());
+			let limit = self.search(&next, cost + step.energy);
+			next_limit = next_limit.into_iter().chain(limit).min();
+			self.moves.pop();
+		}
+
+		next_limit
+	}
+}
+
+impl SearchStrategy for IdaStar {
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search {
+		let mut limit = heuristic(start);
+		let mut tracker = Tracker::new(progress);
+
+		loop {
+			let mut iteration = Iteration {
+				limit,
+				reached: HashMap::new(),
+				moves: vec![],
+				best: None,
+				tracker,
+			};
+			let next_limit = iteration.search(start, 0);
+			tracker = iteration.tracker;
+
+			match (iteration.best, next_limit) {
+				(None, Some(next_limit)) => limit = next_limit.max(limit + limit / 16),
+				(plan, _) => return tracker.finish(plan, true),
+			}
+		}
+	}
+}
+
+/// Depth first search, remembering the cheapest way to organize the amphipods from every map
+/// it has searched.
+///
+/// An amphipod never goes back to a square it left, so the search can't go around in circles.
+pub struct MemoizedDfs;
+
+/// The cheapest energy needed to organize the amphipods from each map, and the first move to
+/// make, or `None` if they can't be organized from there.
+type Memo = HashMap<State, Option<(i32, Option<Move>)>>;
+
+/// The cheapest energy needed to organize the amphipods from `map`, `depth` moves from the start.
+fn cheapest_from(map: &Map, depth: usize, memo: &mut Memo, tracker: &mut Tracker) -> Option<i32> {
+	let state = map.pack();
+	if let Some(known) = memo.get(&state) {
+		tracker.stats.duplicates += 1;
+		return known.map(|(energy, _)| energy);
+	}
+
+	let mut cheapest = None;
+	match map.amphipods_organized() {
+		true => {
+			tracker.stats.goal_hits += 1;
+			cheapest = Some((0, None));
+		}
+		false => {
+			tracker.expand();
+
+			for (step, next) in map.successors() {
+				if next.is_deadlocked() {
+					tracker.stats.pruned += 1;
+					continue;
+				}
+
+				tracker.push(depth + 1);
+				if let Some(energy) = cheapest_from(&next, depth + 1, memo, tracker).map(|energy| energy + step.energy) {
+					if cheapest.is_none_or(|(cheapest, _)| energy < cheapest) {
+						cheapest = Some((energy, Some(step)));
+					}
+				}
+			}
+		}
+	}
+
+	memo.insert(state, cheapest);
+	tracker.visit(memo.len());
+	cheapest.map(|(energy, _)| energy)
+}
+
+impl SearchStrategy for MemoizedDfs {
+	fn search_with_progress(&self, start: &Map, progress: Progress) -> Search {
+		let mut memo = Memo::new();
+		let mut tracker = Tracker::new(progress);
+
+		let plan = cheapest_from(start, 0, &mut memo, &mut tracker).map(|energy| {
+			let mut moves = vec![];
+			let mut current = start.clone();
+			while let Some(Some((_, Some(step)))) = memo.get(&current.pack()) {
+				moves.push(*step);
+				current = current.apply(step).expect("the remembered move can't be made");
+			}
+
+			Plan { energy, moves }
+		});
+
+		tracker.finish(plan, true)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::fixtures::example;
+	use crate::amphipod::generate::{shuffled, Rng};
+	use crate::amphipod::validate;
+
+	#[test]
+	fn test_every_strategy_should_find_the_cheapest_valid_plan() {
+		// Given
+		let map = example();
+
+		for name in STRATEGIES {
+			// When
+			let plan = strategy(name).unwrap().search(&map).plan.unwrap();
+			let steps: Vec<validate::Step> = plan.moves.iter().copied().map(validate::Step::from).collect();
+
+			// Then
+			let checked = validate::check(&map, &steps).unwrap();
+			assert_eq!((12521, 12521, true), (plan.energy, checked.plan.energy, checked.organized), "{}", name);
+		}
+	}
+
+	#[test]
+	fn test_heuristic_should_expand_fewer_maps() {
+		// Given
+		let map = example();
+
+		// When
+		let dijkstra = Dijkstra.search(&map).stats.expanded;
+		let astar = AStar.search(&map).stats.expanded;
+
+		// Then
+		assert!(astar < dijkstra, "{} < {}", astar, dijkstra);
+	}
+
+	#[test]
+	fn test_stats_should_add_up() {
+		// Given
+		let map = example();
+
+		for name in STRATEGIES {
+			// When
+			let stats = strategy(name).unwrap().search(&map).stats;
+
+			// Then
+			assert!(stats.goal_hits >= 1, "{}: {}", name, stats);
+			assert!(stats.expanded <= stats.visited + stats.duplicates, "{}: {}", name, stats);
+			assert!(stats.peak_queue <= stats.pushed, "{}: {}", name, stats);
+		}
+	}
+
+	#[test]
+	fn test_progress_should_be_reported_every_interval() {
+		// Given
+		let mut reported = vec![];
+		let mut progress = |stats: &SearchStats| reported.push(stats.expanded);
+		let mut tracker = Tracker::new(&mut progress);
+
+		// When
+		(0..(2 * PROGRESS_INTERVAL + 1)).for_each(|_| tracker.expand());
+		let result = tracker.finish(None, true).stats.expanded;
+
+		// Then
+		assert_eq!(2 * PROGRESS_INTERVAL + 1, result);
+		assert_eq!(vec![PROGRESS_INTERVAL, 2 * PROGRESS_INTERVAL], reported);
+	}
+
+	#[test]
+	fn test_unknown_strategy_should_not_exist() {
+		assert!(strategy("bogo").is_none());
+	}
+
+	#[test]
+	fn test_observer_should_see_every_map_pushed_and_expanded() {
+		// Given
+		let map = example();
+		let (mut reached, mut expanded) = (0, 0);
+		let mut observer = |event| match event {
+			Event::Reached { .. } => reached += 1,
+			Event::Expanded { .. } => expanded += 1,
+		};
+
+		// When
+		let stats = <BestFirst>::a_star().search_observed(&map, &mut |_| {}, &mut observer).stats;
+
+		// Then
+		assert_eq!((stats.pushed, stats.expanded), (reached, expanded));
+	}
+
+	#[test]
+	fn test_best_first_should_stop_at_the_first_organized_map() {
+		// Given
+		let map = example();
+
+		for strategy in [&Dijkstra as &dyn SearchStrategy, &AStar] {
+			// When
+			let result = strategy.search(&map);
+
+			// Then
+			assert_eq!((true, 1, Some(12521)), (result.optimal, result.stats.goal_hits, result.stats.best));
+		}
+	}
+
+	#[test]
+	fn test_best_first_should_search_burrows_with_high_energy_costs() {
+		// Given
+		let map: Map = format!("{}\nD=3500000\n", example()).parse().unwrap();
+
+		for strategy in [&Dijkstra as &dyn SearchStrategy, &AStar, &Anytime::new(Duration::from_secs(60))] {
+			// When
+			let result = strategy.search(&map);
+
+			// Then
+			assert_eq!((true, Some(42000521)), (result.optimal, result.plan.map(|plan| plan.energy)));
+		}
+	}
+
+	#[test]
+	fn test_anytime_should_report_ever_cheaper_plans() {
+		// Given
+		let map = example();
+		let mut reported = vec![];
+		let mut progress = |stats: &SearchStats| reported.extend(stats.best);
+
+		// When
+		let result = Anytime::new(Duration::from_secs(60)).search_with_progress(&map, &mut progress);
+
+		// Then
+		assert_eq!((true, Some(12521)), (result.optimal, result.plan.map(|plan| plan.energy)));
+		assert!(reported.windows(2).all(|pair| pair[1] < pair[0]), "{:?}", reported);
+		assert_eq!(Some(&12521), reported.last());
+	}
+
+	#[test]
+	fn test_anytime_out_of_time_should_not_be_optimal() {
+		// Given
+		let map = example();
+
+		// When
+		let result = Anytime::new(Duration::ZERO).search(&map);
+
+		// Then
+		assert_eq!((false, None), (result.optimal, result.plan));
+	}
+
+	#[test]
+	fn test_heuristic_should_never_overestimate() {
+		let seeds = (1..=20).map(|seed| (2, seed)).chain((1..=5).map(|seed| (3, seed)));
+		for (depth, seed) in seeds {
+			// Given
+			let map = shuffled(3, depth, &mut Rng::new(seed)).unwrap();
+			let mut memo = Memo::new();
+
+			// When
+			cheapest_from(&map, 0, &mut memo, &mut Tracker::new(&mut |_| {}));
+
+			// Then
+			for (state, cheapest) in memo {
+				let map = state.unpack(&map.burrow);
+				if let Some((energy, _)) = cheapest {
+					assert!(heuristic(&map) <= energy, "{} > {}\n{}", heuristic(&map), energy, map);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_heuristic_should_be_exact_for_the_last_moves() {
+		// Given
+		let map = example();
+		let mut memo = Memo::new();
+
+		// When
+		cheapest_from(&map, 0, &mut memo, &mut Tracker::new(&mut |_| {}));
+
+		// Then
+		for (state, cheapest) in memo {
+			let map = state.unpack(&map.burrow);
+			let moving = map.amphipods.iter().filter(|amphipod| !map.is_settled(amphipod)).count();
+			match (moving, cheapest) {
+				(0, _) => assert_eq!(0, heuristic(&map)),
+				(1, Some((energy, _))) => assert_eq!(energy, heuristic(&map), "\n{}", map),
+				_ => {}
+			}
+		}
+	}
+}
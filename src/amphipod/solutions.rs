@@ -0,0 +1,273 @@
+//! Every cheapest way to organize the amphipods, and the cheapest ways after them.
+//!
+//! A search stops at the first cheapest plan it finds. [`Solutions`] instead works out the
+//! cheapest energy needed to organize the amphipods from every map that can be reached, like
+//! [`MemoizedDfs`](super::search::MemoizedDfs) does. A move keeps a plan cheapest exactly when its
+//! energy and the cheapest energy needed after it add up to the cheapest energy needed before it,
+//! which is enough to count the cheapest plans without listing them, to list them, and to list all
+//! plans in order of their energy.
+//!
+//! Plans are told apart by their moves, so making the same moves in another order is another plan.
+
+use std::collections::HashMap;
+
+use super::frontier::{BucketQueue, Frontier};
+use super::{Map, Move, Plan, State};
+
+/// The ways to organize the amphipods from a map.
+#[derive(Clone, Debug)]
+pub struct Solutions {
+	start: Map,
+	/// The cheapest energy needed to organize the amphipods from each map searched, or `None` if
+	/// they can't be organized from there. Deadlocked maps aren't searched.
+	cheapest: HashMap<State, Option<i32>>,
+}
+
+impl Solutions {
+	/// Work out the cheapest energy needed from every map that can be reached from `start`.
+	pub fn new(start: &Map) -> Self {
+		let mut solutions = Solutions {
+			start: start.clone(),
+			cheapest: HashMap::new(),
+		};
+		solutions.search(start);
+
+		solutions
+	}
+
+	/// Search below `map`, returning the cheapest energy needed to organize the amphipods from it.
+	fn search(&mut self, map: &Map) -> Option<i32> {
+		let state = map.pack();
+		if let Some(known) = self.cheapest.get(&state) {
+			return *known;
+		}
+
+		let cheapest = match map.amphipods_organized() {
+			true => Some(0),
+			false => map
+				.successors()
+				.into_iter()
+				.filter(|(_, next)| !next.is_deadlocked())
+				.filter_map(|(step, next)| self.search(&next).map(|energy| energy + step.energy))
+				.min(),
+		};
+		self.cheapest.insert(state, cheapest);
+
+		cheapest
+	}
+
+	/// The cheapest energy needed from a map that has been searched, or `None` if the amphipods
+	/// can't be organized from it.
+	fn remaining(&self, map: &Map) -> Option<i32> {
+		self.cheapest.get(&map.pack()).copied().flatten()
+	}
+
+	/// The moves from `map` after which the amphipods can still be organized, with the map after
+	/// each of them and the cheapest energy needed from there.
+	fn moves(&self, map: &Map) -> Vec<(Move, Map, i32)> {
+		map.successors()
+			.into_iter()
+			.filter_map(|(step, next)| Some((step, next.clone(), self.remaining(&next)?)))
+			.collect()
+	}
+
+	/// The moves from `map` that keep the plan cheapest, with the map after each of them.
+	fn cheapest_moves(&self, map: &Map) -> Vec<(Move, Map)> {
+		let energy = self.remaining(map);
+
+		self.moves(map)
+			.into_iter()
+			.filter(|(step, _, remaining)| energy == Some(step.energy + remaining))
+			.map(|(step, next, _)| (step, next))
+			.collect()
+	}
+
+	/// The energy the cheapest plans need, or `None` if the amphipods can't be organized.
+	pub fn energy(&self) -> Option<i32> {
+		self.remaining(&self.start)
+	}
+
+	/// The number of cheapest plans, or [`u64::MAX`] if there are at least that many.
+	pub fn count(&self) -> u64 {
+		match self.energy() {
+			Some(_) => self.count_from(&self.start, &mut HashMap::new()),
+			None => 0,
+		}
+	}
+
+	/// The number of cheapest plans from `map`, remembering it for every map counted.
+	fn count_from(&self, map: &Map, counted: &mut HashMap<State, u64>) -> u64 {
+		if map.amphipods_organized() {
+			return 1;
+		}
+		let state = map.pack();
+		if let Some(count) = counted.get(&state) {
+			return *count;
+		}
+
+		let count = self
+			.cheapest_moves(map)
+			.iter()
+			.fold(0_u64, |count, (_, next)| count.saturating_add(self.count_from(next, counted)));
+		counted.insert(state, count);
+
+		count
+	}
+
+	/// The cheapest plans, at most `cap` of them.
+	pub fn cheapest(&self, cap: usize) -> Vec<Plan> {
+		let mut plans = vec![];
+		if let Some(energy) = self.energy() {
+			self.list_from(&self.start, &mut vec![], energy, cap, &mut plans);
+		}
+
+		plans
+	}
+
+	/// Add the cheapest plans going through `map`, reached with `moves`, to `plans` until there are
+	/// `cap` of them.
+	fn list_from(&self, map: &Map, moves: &mut Vec<Move>, energy: i32, cap: usize, plans: &mut Vec<Plan>) {
+		if plans.len() >= cap {
+			return;
+		}
+		if map.amphipods_organized() {
+			plans.push(Plan {
+				energy,
+				moves: moves.clone(),
+			});
+			return;
+		}
+
+		for (step, next) in self.cheapest_moves(map) {
+			moves.push(step);
+			self.list_from(&next, moves, energy, cap, plans);
+			moves.pop();
+		}
+	}
+
+	/// The `k` cheapest plans, cheapest first, or all of them if there are fewer.
+	///
+	/// Plans are taken out of a queue ordered by the energy spent plus the cheapest energy still
+	/// needed, which is the energy of the cheapest plan they can become. So they come out finished
+	/// in order of their energy, and every plan in the queue can be finished.
+	pub fn best(&self, k: usize) -> Vec<Plan> {
+		let mut plans = vec![];
+		let mut queue = BucketQueue::<(Map, Vec<Move>)>::default();
+		if let Some(energy) = self.energy() {
+			queue.push((self.start.clone(), vec![]), energy);
+		}
+
+		while plans.len() < k {
+			let Some(((map, moves), energy)) = queue.pop() else {
+				break;
+			};
+			if map.amphipods_organized() {
+				plans.push(Plan { energy, moves });
+				continue;
+			}
+
+			let spent = energy - self.remaining(&map).expect("only maps the amphipods can be organized from are queued");
+			for (step, next, remaining) in self.moves(&map) {
+				let mut moves = moves.clone();
+				moves.push(step);
+				queue.push((next, moves), spent + step.energy + remaining);
+			}
+		}
+
+		plans
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::fixtures::swapped_rooms;
+	use std::collections::HashSet;
+
+	/// Whether every plan organizes the amphipods from `start` with the energy it claims.
+	fn all_organize(start: &Map, plans: &[Plan]) -> bool {
+		plans.iter().all(|plan| {
+			let maps = plan.replay(start).unwrap();
+			let energy: i32 = plan.moves.iter().map(|step| step.energy).sum();
+			maps.last().unwrap().amphipods_organized() && energy == plan.energy
+		})
+	}
+
+	#[test]
+	fn test_every_cheapest_plan_should_be_listed_and_counted() {
+		// Given
+		let map = swapped_rooms();
+		let solutions = Solutions::new(&map);
+
+		// When
+		let plans = solutions.cheapest(usize::MAX);
+
+		// Then
+		let distinct: HashSet<&Vec<Move>> = plans.iter().map(|plan| &plan.moves).collect();
+		assert_eq!(Some(46), solutions.energy());
+		assert!(plans.len() > 1, "{:?}", plans);
+		assert_eq!((solutions.count(), plans.len()), (plans.len() as u64, distinct.len()));
+		assert!(plans.iter().all(|plan| plan.energy == 46));
+		assert!(all_organize(&map, &plans));
+	}
+
+	#[test]
+	fn test_cheapest_plans_should_keep_to_the_cap() {
+		// Given
+		let solutions = Solutions::new(&swapped_rooms());
+
+		// When
+		let result = solutions.cheapest(1);
+
+		// Then
+		assert_eq!(1, result.len());
+		assert_eq!(solutions.cheapest(usize::MAX)[0], result[0]);
+	}
+
+	#[test]
+	fn test_best_plans_should_come_cheapest_first() {
+		// Given
+		let map = swapped_rooms();
+		let solutions = Solutions::new(&map);
+		let count = solutions.count() as usize;
+
+		// When
+		let plans = solutions.best(count + 20);
+
+		// Then
+		let distinct: HashSet<&Vec<Move>> = plans.iter().map(|plan| &plan.moves).collect();
+		assert_eq!((count + 20, count + 20), (plans.len(), distinct.len()));
+		assert!(plans.windows(2).all(|pair| pair[0].energy <= pair[1].energy));
+		assert!(plans[..count].iter().all(|plan| plan.energy == 46));
+		assert!(plans[count].energy > 46);
+		assert!(all_organize(&map, &plans));
+	}
+
+	#[test]
+	fn test_organized_map_should_have_a_single_empty_plan() {
+		// Given
+		let map: Map = "#############\n#...........#\n###A#B#C#D###\n  #A#B#C#D#\n  #########\n".parse().unwrap();
+
+		// When
+		let solutions = Solutions::new(&map);
+
+		// Then
+		let empty = Plan { energy: 0, moves: vec![] };
+		assert_eq!(1, solutions.count());
+		assert_eq!(vec![empty.clone()], solutions.cheapest(10));
+		assert_eq!(vec![empty], solutions.best(10));
+	}
+
+	#[test]
+	fn test_deadlocked_map_should_have_no_plans() {
+		// Given
+		let map: Map = "#############\n#...D...A...#\n###.#B#C#.###\n  #A#B#C#D#\n  #########\n".parse().unwrap();
+
+		// When
+		let solutions = Solutions::new(&map);
+
+		// Then
+		assert_eq!((None, 0), (solutions.energy(), solutions.count()));
+		assert!(solutions.cheapest(10).is_empty() && solutions.best(10).is_empty());
+	}
+}
@@ -0,0 +1,196 @@
+//! Maps packed into a single number, for keeping many of them around while searching.
+//!
+//! Every square an amphipod can be in gets [`BITS`] bits, holding 0 for an empty square or the
+//! number of the room of the amphipod in it, counted from 1. The standard burrow has 27 such
+//! squares, which fit in a `u128` with room to spare. The maps of burrows that don't fit, see
+//! [`fits`], get a byte for every square instead, which takes more memory but works the same.
+//!
+//! Whether an amphipod has moved isn't packed. An amphipod in the hallway has always moved, and
+//! one that has moved into its own room is settled there, which is the same as if it had never
+//! moved.
+
+use std::sync::Arc;
+
+use super::{Amphipod, Burrow, Map};
+
+/// The number of bits for each square.
+pub const BITS: usize = 3;
+
+/// The mask for the bits of a single square.
+const MASK: u128 = (1 << BITS) - 1;
+
+/// A map packed into a single number, or a list of bytes for large burrows. See the
+/// [module documentation](self) for how.
+#[derive(Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+pub struct State(Squares);
+
+/// The room of the amphipod in each square, counted from 1, or 0 for an empty square.
+#[derive(Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+enum Squares {
+	Packed(u128),
+	Wide(Box<[u8]>),
+}
+
+/// Whether the maps of `burrow` can be packed into a single number.
+pub fn fits(burrow: &Burrow) -> bool {
+	burrow.cells.len() * BITS <= u128::BITS as usize && burrow.rooms.len() <= MASK as usize
+}
+
+impl Map {
+	/// Pack the amphipods into a [`State`].
+	///
+	/// ## Panics
+	///
+	/// If an amphipod isn't in a square of the burrow, or there is no room for its kind.
+	pub fn pack(&self) -> State {
+		let burrow = &self.burrow;
+
+		let squares = self.amphipods.iter().map(|amphipod| {
+			let cell = burrow
+				.cell(&amphipod.position)
+				.unwrap_or_else(|| panic!("amphipod {} is outside the burrow", amphipod.color));
+			let room = burrow
+				.rooms
+				.iter()
+				.position(|room| room.color == amphipod.color)
+				.unwrap_or_else(|| panic!("there is no room for amphipod {}", amphipod.color));
+
+			(cell, room + 1)
+		});
+
+		match fits(burrow) {
+			true => State(Squares::Packed(
+				squares.fold(0, |packed, (cell, room)| packed | ((room as u128) << (cell * BITS))),
+			)),
+			false => {
+				let mut wide = vec![0; burrow.cells.len()];
+				for (cell, room) in squares {
+					// The drawing is at most 255 columns wide, so there are fewer rooms than that.
+					wide[cell] = room as u8;
+				}
+
+				State(Squares::Wide(wide.into()))
+			}
+		}
+	}
+}
+
+impl State {
+	/// The room of the amphipod in the square numbered `cell`, counted from 1, or 0 if it's empty.
+	fn room(&self, cell: usize) -> usize {
+		match &self.0 {
+			Squares::Packed(packed) => ((packed >> (cell * BITS)) & MASK) as usize,
+			Squares::Wide(wide) => wide[cell] as usize,
+		}
+	}
+
+	/// Unpack the amphipods into a map of `burrow`, the burrow the state was packed from.
+	pub fn unpack(&self, burrow: &Arc<Burrow>) -> Map {
+		let amphipods = burrow
+			.cells
+			.iter()
+			.enumerate()
+			.filter_map(|(cell, position)| match self.room(cell) {
+				0 => None,
+				room => Some(Amphipod {
+					color: burrow.rooms[room - 1].color,
+					position: *position,
+					has_moved: burrow.in_hallway(position),
+				}),
+			})
+			.collect();
+
+		Map::new(Arc::clone(burrow), amphipods)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::cave;
+	use crate::amphipod::fixtures::example;
+
+	#[test]
+	fn test_standard_burrow_should_fit() {
+		// Given
+		let burrow = Burrow::standard(4);
+
+		// When
+		let result = (burrow.cells.len(), fits(&burrow));
+
+		// Then
+		assert_eq!((27, true), result);
+	}
+
+	#[test]
+	fn test_deep_burrow_should_not_fit() {
+		// Given
+		let lines: Vec<String> = cave(8).lines().map(String::from).collect();
+		let burrow = Burrow::from_lines(&lines);
+
+		// When
+		let result = fits(&burrow);
+
+		// Then
+		assert!(!result);
+	}
+
+	#[test]
+	fn test_unpacked_map_should_be_the_same() {
+		// Given
+		let map = example();
+
+		// When
+		let result = map.pack().unpack(&map.burrow);
+
+		// Then
+		assert_eq!(map, result);
+	}
+
+	#[test]
+	fn test_every_successor_should_pack_differently() {
+		// Given
+		let map = example();
+
+		// When
+		let mut states: Vec<State> = map.successors().iter().map(|(_, next)| next.pack()).collect();
+
+		// Then
+		let count = states.len();
+		states.sort();
+		states.dedup();
+		assert_eq!(count, states.len());
+		assert!(!states.contains(&map.pack()));
+	}
+
+	#[test]
+	fn test_unpacked_successors_should_move_the_same() {
+		// Given
+		let map = example();
+		let (_, next) = map.successors().remove(0);
+
+		// When
+		let result = next.pack().unpack(&map.burrow);
+
+		// Then
+		assert_eq!(next.successors().len(), result.successors().len());
+		assert_eq!(next.to_string(), result.to_string());
+	}
+
+	#[test]
+	fn test_deep_burrow_should_unpack_the_same() {
+		// Given
+		let room = "  #A#B#C#D#\n";
+		let input = format!("#############\n#...........#\n###B#A#C#D###\n{}  #########\n", room.repeat(7));
+		let map: Map = input.lines().map(String::from).collect();
+
+		// When
+		let state = map.pack();
+		let result = state.unpack(&map.burrow);
+
+		// Then
+		assert!(!fits(&map.burrow));
+		assert_eq!(map, result);
+		assert!(map.successors().iter().all(|(_, next)| next.pack() != state));
+	}
+}
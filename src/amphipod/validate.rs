@@ -0,0 +1,443 @@
+//! Checking a list of moves against the rules of the burrow.
+//!
+//! This is meant for solutions worked out by hand or by other solvers, so it follows the rules
+//! of the puzzle rather than the stricter moves of the search:
+//!
+//! * An amphipod that leaves its room stops in the hallway, but never right outside a room.
+//! * Once in the hallway an amphipod stays there until it moves into its own room.
+//! * An amphipod only moves into its own room, and only when there are no other kinds of
+//!   amphipods in it.
+//! * Amphipods can't move through each other.
+//!
+//! Moves are written one per line, like `A (3, 2) -> (2, 1): 20`. The kind and the energy are
+//! optional, but when they are given they have to be right.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Map, Move, Plan, Position};
+
+/// A move as written in a proposed solution.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Step {
+	/// The kind of amphipod that is expected to move.
+	pub color: Option<char>,
+	pub from: Position,
+	pub to: Position,
+	/// The energy the move is expected to cost.
+	pub energy: Option<i32>,
+}
+
+impl From<Move> for Step {
+	fn from(step: Move) -> Self {
+		Step {
+			color: Some(step.color),
+			from: step.from,
+			to: step.to,
+			energy: Some(step.energy),
+		}
+	}
+}
+
+impl fmt::Display for Step {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		if let Some(color) = self.color {
+			write!(f, "{} ", color)?;
+		}
+		write!(f, "({}, {}) -> ({}, {})", self.from.x, self.from.y, self.to.x, self.to.y)?;
+		if let Some(energy) = self.energy {
+			write!(f, ": {}", energy)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// A line that could not be read as a [`Step`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepError(pub String);
+
+impl fmt::Display for StepError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid move {:?}, expected something like \"A (3, 2) -> (2, 1): 20\"", self.0)
+	}
+}
+
+impl error::Error for StepError {}
+
+/// Parse a position written as `(x, y)`.
+fn position(s: &str) -> Option<Position> {
+	let (x, y) = s.trim().strip_prefix('(')?.strip_suffix(')')?.split_once(',')?;
+
+	Some(Position {
+		x: x.trim().parse().ok()?,
+		y: y.trim().parse().ok()?,
+	})
+}
+
+impl FromStr for Step {
+	type Err = StepError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = || StepError(s.to_string());
+
+		let (from, rest) = s.split_once("->").ok_or_else(error)?;
+		let (to, energy) = match rest.split_once(':') {
+			Some((to, energy)) => (to, Some(energy.trim().parse().map_err(|_| error())?)),
+			None => (rest, None),
+		};
+
+		let from = from.trim();
+		let (color, from) = match from.chars().next() {
+			Some(c) if c.is_ascii_uppercase() => (Some(c), &from[1..]),
+			_ => (None, from),
+		};
+
+		Ok(Step {
+			color,
+			from: position(from).ok_or_else(error)?,
+			to: position(to).ok_or_else(error)?,
+			energy,
+		})
+	}
+}
+
+/// Read the steps of a proposed solution, one per line.
+///
+/// Blank lines and lines starting with `#` are skipped.
+pub fn parse_steps(input: &str) -> Result<Vec<Step>, StepError> {
+	input
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(str::parse)
+		.collect()
+}
+
+/// Why a step breaks the rules.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Rule {
+	/// There is no amphipod where the step starts.
+	NoAmphipod,
+	/// The amphipod is of another kind than the step says.
+	WrongKind { expected: char, found: char },
+	/// The step ends inside a wall.
+	IntoWall,
+	/// The step ends on another amphipod.
+	Occupied,
+	/// The step ends where it started, or in the room it started in.
+	NotAMove,
+	/// The amphipod stops right outside a room.
+	NotAStop,
+	/// The amphipod is in the hallway and has to move into its own room.
+	MustGoHome,
+	/// The amphipod has already moved into its own room.
+	AlreadyHome,
+	/// The amphipod moves into a room that isn't its own.
+	WrongRoom,
+	/// The amphipod moves into its own room while other kinds are still in it.
+	RoomNotClean,
+	/// Another amphipod is in the way.
+	Blocked { at: Position },
+	/// The step costs another amount of energy than the step says.
+	WrongEnergy { expected: i32, found: i32 },
+}
+
+impl fmt::Display for Rule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		match self {
+			Rule::NoAmphipod => write!(f, "there is no amphipod there"),
+			Rule::WrongKind { expected, found } => write!(f, "expected amphipod {}, found {}", expected, found),
+			Rule::IntoWall => write!(f, "moves into a wall"),
+			Rule::Occupied => write!(f, "moves onto another amphipod"),
+			Rule::NotAMove => write!(f, "doesn't leave the room"),
+			Rule::NotAStop => write!(f, "stops right outside a room"),
+			Rule::MustGoHome => write!(f, "has to move from the hallway into its own room"),
+			Rule::AlreadyHome => write!(f, "has already moved into its own room"),
+			Rule::WrongRoom => write!(f, "moves into another kind's room"),
+			Rule::RoomNotClean => write!(f, "moves into a room with other kinds of amphipods"),
+			Rule::Blocked { at } => write!(f, "is blocked at ({}, {})", at.x, at.y),
+			Rule::WrongEnergy { expected, found } => {
+				write!(f, "costs {} energy, not {}", found, expected)
+			}
+		}
+	}
+}
+
+/// The first step of a proposed solution that breaks the rules.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Violation {
+	/// The number of the step, starting at 1.
+	pub number: usize,
+	pub step: Step,
+	pub rule: Rule,
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(f, "step {} ({}): {}", self.number, self.step, self.rule)
+	}
+}
+
+impl error::Error for Violation {}
+
+/// A proposed solution that follows the rules.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Checked {
+	/// The moves with the energy they actually cost.
+	pub plan: Plan,
+	/// Whether the amphipods are organized after the last move.
+	pub organized: bool,
+}
+
+/// Check a single step from the map, returning the move it makes.
+pub(super) fn check_step(map: &Map, step: &Step) -> Result<(usize, Move), Rule> {
+	let burrow = &map.burrow;
+
+	let index = map
+		.amphipods
+		.iter()
+		.position(|amphipod| amphipod.position == step.from)
+		.ok_or(Rule::NoAmphipod)?;
+	let amphipod = &map.amphipods[index];
+
+	if let Some(expected) = step.color.filter(|color| *color != amphipod.color) {
+		return Err(Rule::WrongKind {
+			expected,
+			found: amphipod.color,
+		});
+	}
+
+	let into_room = burrow.room_at(&step.to);
+	if into_room.is_none() && !burrow.hallway.contains(&step.to) {
+		return Err(Rule::IntoWall);
+	}
+	if step.from == step.to || into_room.is_some_and(|room| room.contains(&step.from)) {
+		return Err(Rule::NotAMove);
+	}
+	if map.occupant(&step.to).is_some() {
+		return Err(Rule::Occupied);
+	}
+
+	match (burrow.in_hallway(&amphipod.position), into_room) {
+		(true, None) => return Err(Rule::MustGoHome),
+		(false, _) if amphipod.has_moved => return Err(Rule::AlreadyHome),
+		(false, None) if !burrow.stops.contains(&step.to) => return Err(Rule::NotAStop),
+		(_, Some(room)) if room.color != amphipod.color => return Err(Rule::WrongRoom),
+		(_, Some(room)) if !map.room_is_clean(room) => return Err(Rule::RoomNotClean),
+		_ => {}
+	}
+
+	let path = map.path(&step.from, &step.to);
+	if let Some(at) = path.iter().find(|position| map.occupant(position).is_some()) {
+		return Err(Rule::Blocked { at: *at });
+	}
+
+	let energy = path.len() as i32 * amphipod.energy_cost(burrow);
+	if let Some(expected) = step.energy.filter(|expected| *expected != energy) {
+		return Err(Rule::WrongEnergy {
+			expected,
+			found: energy,
+		});
+	}
+
+	Ok((
+		index,
+		Move {
+			color: amphipod.color,
+			from: step.from,
+			to: step.to,
+			energy,
+		},
+	))
+}
+
+/// Check the steps of a proposed solution, starting from `start`.
+///
+/// Returns the moves with their total energy, or the first step that breaks the rules.
+pub fn check(start: &Map, steps: &[Step]) -> Result<Checked, Violation> {
+	let mut map = start.clone();
+	let mut moves = vec![];
+
+	for (i, step) in steps.iter().enumerate() {
+		let (index, checked) = check_step(&map, step).map_err(|rule| Violation {
+			number: i + 1,
+			step: *step,
+			rule,
+		})?;
+
+		map = map.moved(index, step.to);
+		moves.push(checked);
+	}
+
+	Ok(Checked {
+		plan: Plan {
+			energy: moves.iter().map(|step| step.energy).sum(),
+			moves,
+		},
+		organized: map.amphipods_organized(),
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::amphipod::easiest_moves;
+	use crate::amphipod::fixtures::swapped_rooms;
+
+	fn steps(input: &str) -> Vec<Step> {
+		parse_steps(input).unwrap()
+	}
+
+	#[test]
+	fn test_step_should_be_parsed_with_kind_and_energy() {
+		// Given
+		let input = "A (3, 2) -> (2, 1): 20";
+
+		// When
+		let result = input.parse::<Step>();
+
+		// Then
+		assert_eq!(
+			Ok(Step {
+				color: Some('A'),
+				from: Position { x: 3, y: 2 },
+				to: Position { x: 2, y: 1 },
+				energy: Some(20),
+			}),
+			result
+		);
+	}
+
+	#[test]
+	fn test_step_should_be_parsed_without_kind_and_energy() {
+		// Given
+		let input = "(3,2) -> (2,1)";
+
+		// When
+		let result = input.parse::<Step>().map(|step| (step.color, step.energy));
+
+		// Then
+		assert_eq!(Ok((None, None)), result);
+	}
+
+	#[test]
+	fn test_garbled_step_should_be_an_error() {
+		// Given
+		let input = "A (3, 2) to (2, 1)";
+
+		// When
+		let result = input.parse::<Step>();
+
+		// Then
+		assert_eq!(Err(StepError(String::from(input))), result);
+	}
+
+	#[test]
+	fn test_solver_plan_should_be_valid() {
+		// Given
+		let map = swapped_rooms();
+		let plan = easiest_moves(map.clone()).unwrap();
+		let steps: Vec<Step> = plan.moves.iter().copied().map(Step::from).collect();
+
+		// When
+		let result = check(&map, &steps);
+
+		// Then
+		assert_eq!(Ok(Checked { plan, organized: true }), result);
+	}
+
+	#[test]
+	fn test_direct_move_between_rooms_should_be_valid() {
+		// Given
+		let map = swapped_rooms();
+		let steps = steps("B (3, 2) -> (2, 1): 20\nA (5, 2) -> (3, 2): 4\nB (2, 1) -> (5, 2): 40\n");
+
+		// When
+		let result = check(&map, &steps).map(|checked| (checked.plan.energy, checked.organized));
+
+		// Then
+		assert_eq!(Ok((64, true)), result);
+	}
+
+	#[test]
+	fn test_unfinished_moves_should_not_be_organized() {
+		// Given
+		let map = swapped_rooms();
+		let steps = steps("# Just get out of the way\nB (3, 2) -> (2, 1)\n");
+
+		// When
+		let result = check(&map, &steps).map(|checked| (checked.plan.energy, checked.organized));
+
+		// Then
+		assert_eq!(Ok((20, false)), result);
+	}
+
+	#[test]
+	fn test_stopping_outside_a_room_should_be_reported() {
+		// Given
+		let map = swapped_rooms();
+		let steps = steps("B (3, 2) -> (3, 1)");
+
+		// When
+		let result = check(&map, &steps).map_err(|violation| (violation.number, violation.rule));
+
+		// Then
+		assert_eq!(Err((1, Rule::NotAStop)), result);
+	}
+
+	#[test]
+	fn test_moving_in_the_hallway_should_be_reported() {
+		// Given
+		let map = swapped_rooms();
+		let steps = steps("B (3, 2) -> (2, 1)\nB (2, 1) -> (1, 1)");
+
+		// When
+		let result = check(&map, &steps).map_err(|violation| (violation.number, violation.rule));
+
+		// Then
+		assert_eq!(Err((2, Rule::MustGoHome)), result);
+	}
+
+	#[test]
+	fn test_moving_into_a_dirty_room_should_be_reported() {
+		// Given
+		let map: Map = "#############\n#...........#\n###A#B#C#D###\n  #B#A#C#D#\n  #########\n"
+			.parse()
+			.unwrap();
+		let steps = steps("B (5, 2) -> (4, 1)\nB (4, 1) -> (5, 2)");
+
+		// When
+		let result = check(&map, &steps).map_err(|violation| (violation.number, violation.rule));
+
+		// Then
+		assert_eq!(Err((2, Rule::RoomNotClean)), result);
+	}
+
+	#[test]
+	fn test_blocked_path_should_be_reported() {
+		// Given
+		let map = swapped_rooms();
+		let steps = steps("B (3, 2) -> (4, 1)\nA (5, 2) -> (2, 1)");
+
+		// When
+		let result = check(&map, &steps).map_err(|violation| violation.rule);
+
+		// Then
+		assert_eq!(Err(Rule::Blocked { at: Position { x: 4, y: 1 } }), result);
+	}
+
+	#[test]
+	fn test_wrong_energy_should_be_reported() {
+		// Given
+		let map = swapped_rooms();
+		let steps = steps("B (3, 2) -> (2, 1): 2");
+
+		// When
+		let result = check(&map, &steps).map_err(|violation| violation.to_string());
+
+		// Then
+		assert_eq!(Err(String::from("step 1 (B (3, 2) -> (2, 1): 2): costs 20 energy, not 2")), result);
+	}
+}
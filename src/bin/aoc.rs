@@ -0,0 +1,546 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use aoc2021::amphipod::generate::{self, Rng};
+use aoc2021::amphipod::graph::Graph;
+use aoc2021::amphipod::play::{Command, Game};
+use aoc2021::amphipod::search::{Anytime, BestFirst, SearchStats};
+use aoc2021::amphipod::solutions::Solutions;
+use aoc2021::amphipod::{replay, search, validate, AStar, Map, MapError, Parallel, Position, SearchStrategy};
+use aoc2021::days;
+use aoc2021::days::day23;
+use aoc2021::solution::Part;
+
+const USAGE: &str = "usage: aoc <day> <part> [input]
+       aoc check <burrow> [moves]
+       aoc search <strategy> [--threads <n>] [--budget <seconds>] <part> [input]
+       aoc search <strategy> --dot <file> [--nodes <n>] <part> [input]
+       aoc play 23 <part> <burrow>
+       aoc replay [--delay <ms>] [--no-color] <part> [input]
+       aoc generate [--seed <n>] [--count <n>] <kinds> <depth> [directory]
+       aoc solutions [--cap <n>] [--best <k>] <part> [input]
+
+Solve part a or b of a day. The input is read from the given file, or from stdin
+if no file is given.
+
+Check a list of moves for the amphipods of day 23, one move per line like
+\"A (3, 2) -> (2, 1): 20\", against the burrow in the given file. The moves are
+read from the given file, or from stdin if no file is given.
+
+Solve part a or b of day 23 with one of the search strategies dijkstra, astar,
+ida, dfs, parallel or anytime, and tell how much work the search took. The
+parallel search uses as many threads as there are processors, unless told
+otherwise. The anytime search reports every cheaper plan it finds, and gives the
+cheapest one so far when its budget of a second, or as many as given, runs out.
+The dijkstra and astar searches can also write the first 1000 maps they reach,
+or as many as given, to a Graphviz file.
+
+Play part a or b of day 23 by hand, with the burrow in the given file.
+
+Show the cheapest way to solve part a or b of day 23 as an animation, redrawing
+the burrow every 100 milliseconds, or as many as given. The amphipods get a
+colour for each kind, unless told otherwise.
+
+Generate random burrows for day 23 with the given number of kinds of amphipods
+and rooms of the given depth, each one solved to make sure it can be. A single
+burrow is written to stdout, more of them are written to files in the given
+directory, together with a list of the energy each one needs and the number of
+maps the solver expanded. The same seed gives the same burrows.
+
+Count the cheapest ways to solve part a or b of day 23, and list the first 10 of
+them, or as many as given. Plans making the same moves in another order count as
+different plans. Or list the given number of cheapest plans, whether they are the
+cheapest there are or not, to see how much more the next best ones need.";
+
+const PLAY_HELP: &str = "Type the square of an amphipod, like 3 2, to pick it, and then the square to move
+it to. The other commands are undo (u), redo (r), hint (h), help (?) and quit (q).";
+
+/// Read the whole puzzle input, either from the named file or from stdin.
+fn read_input(filename: Option<&String>) -> io::Result<String> {
+	match filename {
+		Some(filename) => fs::read_to_string(filename),
+		None => {
+			let mut input = String::new();
+			io::stdin().read_to_string(&mut input)?;
+			Ok(input)
+		}
+	}
+}
+
+/// Read the burrow in the named file, or from stdin if no file is given, exiting if it can't be
+/// read.
+fn read_burrow(filename: Option<&String>) -> Map {
+	read_input(filename)
+		.map_err(|error| error.to_string())
+		.and_then(|input| input.parse().map_err(|error: MapError| error.to_string()))
+		.unwrap_or_else(|error| {
+			eprintln!("failed to read burrow: {}", error);
+			process::exit(1);
+		})
+}
+
+/// Read the burrow for part a or b of day 23 like [`read_burrow`], unfolded for part b, exiting if
+/// it can't be unfolded.
+fn read_map(part: Part, filename: Option<&String>) -> Map {
+	let map = read_burrow(filename);
+
+	match part {
+		Part::A => map,
+		Part::B => day23::unfold(&map).unwrap_or_else(|error| {
+			eprintln!("can't unfold the burrow: {}", error);
+			process::exit(1);
+		}),
+	}
+}
+
+/// Check the moves against the burrow, printing the energy they need or the first illegal move.
+fn check(args: &[String]) {
+	let burrow = match args.first() {
+		Some(burrow) => burrow,
+		None => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	};
+
+	let map = read_burrow(Some(burrow));
+
+	let steps = read_input(args.get(1))
+		.map_err(|error| error.to_string())
+		.and_then(|input| validate::parse_steps(&input).map_err(|error| error.to_string()))
+		.unwrap_or_else(|error| {
+			eprintln!("failed to read moves: {}", error);
+			process::exit(1);
+		});
+
+	match validate::check(&map, &steps) {
+		Ok(checked) if checked.organized => println!("{}", checked.plan.energy),
+		Ok(checked) => {
+			println!("{}", checked.plan.energy);
+			eprintln!("the amphipods are not organized yet");
+			process::exit(1);
+		}
+		Err(violation) => {
+			eprintln!("{}", violation);
+			process::exit(1);
+		}
+	}
+}
+
+/// Take the option `name` and its value out of the arguments, and parse the value.
+///
+/// Exits with the usage if the value is missing or invalid.
+fn take_option<T: FromStr>(args: &mut Vec<String>, name: &str, valid: fn(&T) -> bool) -> Option<T> {
+	let i = args.iter().position(|arg| arg == name)?;
+	let option: Vec<String> = args.drain(i..args.len().min(i + 2)).collect();
+
+	let value = option.get(1).map_or("", String::as_str);
+	let parsed = value.parse().ok().filter(valid);
+	if parsed.is_none() {
+		eprintln!("invalid value {:?} for {}\n\n{}", value, name, USAGE);
+		process::exit(2);
+	}
+
+	parsed
+}
+
+/// Take the flag `name` out of the arguments, returning whether it was there.
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+	let found = args.iter().any(|arg| arg == name);
+	args.retain(|arg| arg != name);
+
+	found
+}
+
+/// Solve day 23 with the named search strategy, printing the energy and how the search went.
+fn search(args: &[String]) {
+	let mut args = args.to_vec();
+	let threads = take_option(&mut args, "--threads", |threads: &usize| *threads > 0);
+	let budget = take_option(&mut args, "--budget", |seconds: &f64| seconds.is_finite() && *seconds >= 0.0);
+	let dot = take_option(&mut args, "--dot", |_: &String| true);
+	let nodes = take_option(&mut args, "--nodes", |nodes: &usize| *nodes > 0);
+
+	let (name, part) = match (args.first(), args.get(1)) {
+		(Some(name), Some(part)) => (name, part),
+		_ => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	};
+
+	let strategy: Box<dyn SearchStrategy> = match (name.as_str(), threads, budget) {
+		("parallel", Some(threads), None) => Box::new(Parallel::new(threads)),
+		("anytime", None, Some(seconds)) => Box::new(Anytime::new(Duration::from_secs_f64(seconds))),
+		(_, Some(_), _) => {
+			eprintln!("--threads only applies to the parallel strategy\n\n{}", USAGE);
+			process::exit(2);
+		}
+		(_, _, Some(_)) => {
+			eprintln!("--budget only applies to the anytime strategy\n\n{}", USAGE);
+			process::exit(2);
+		}
+		(name, None, None) => search::strategy(name).unwrap_or_else(|| {
+			eprintln!("unknown strategy {:?}, expected one of {}\n\n{}", name, search::STRATEGIES.join(", "), USAGE);
+			process::exit(2);
+		}),
+	};
+	if dot.is_some() && !matches!(name.as_str(), "dijkstra" | "astar") {
+		eprintln!("--dot only applies to the dijkstra and astar strategies\n\n{}", USAGE);
+		process::exit(2);
+	}
+	if nodes.is_some() && dot.is_none() {
+		eprintln!("--nodes only applies with --dot\n\n{}", USAGE);
+		process::exit(2);
+	}
+	let part: Part = part.parse().unwrap_or_else(|error| {
+		eprintln!("{}\n\n{}", error, USAGE);
+		process::exit(2);
+	});
+
+	let map = read_map(part, args.get(2));
+
+	let mut reported: Option<SearchStats> = None;
+	let progress = &mut |stats: &SearchStats| {
+		eprintln!("{}", stats);
+		reported = Some(*stats);
+	};
+	let mut graph = Graph::new(nodes.unwrap_or(1000));
+	let observed: Option<BestFirst> = match (name.as_str(), &dot) {
+		("astar", Some(_)) => Some(BestFirst::a_star()),
+		("dijkstra", Some(_)) => Some(BestFirst::dijkstra()),
+		_ => None,
+	};
+	let result = match observed {
+		Some(observed) => observed.search_observed(&map, progress, &mut |event| graph.record(event)),
+		None => strategy.search_with_progress(&map, progress),
+	};
+
+	match &result.plan {
+		Some(plan) => println!("{}", plan.energy),
+		None if result.optimal => println!("the amphipods can't be organized"),
+		None => println!("no plan found in time"),
+	}
+	// The last cheaper plan is often found right before the search ends, with nothing new to say.
+	if reported.is_none_or(|last| SearchStats { elapsed: result.stats.elapsed, ..last } != result.stats) {
+		eprintln!("{}", result.stats);
+	}
+	if !result.optimal {
+		eprintln!("ran out of time before proving there is no cheaper plan");
+	}
+
+	if let Some(dot) = dot {
+		if let Err(error) = fs::write(&dot, graph.to_dot(&map, result.plan.as_ref())) {
+			eprintln!("failed to write {}: {}", dot, error);
+			process::exit(1);
+		}
+		if graph.is_truncated() {
+			eprintln!("only the first {} maps reached were written to {}", graph.len(), dot);
+		}
+	}
+}
+
+/// Solve day 23 and replay the cheapest plan as an animation.
+fn replay(args: &[String]) {
+	let mut args = args.to_vec();
+	let delay = take_option(&mut args, "--delay", |_: &u64| true).unwrap_or(100);
+	let color = !take_flag(&mut args, "--no-color");
+
+	let part: Part = match args.first() {
+		Some(part) => part.parse().unwrap_or_else(|error| {
+			eprintln!("{}\n\n{}", error, USAGE);
+			process::exit(2);
+		}),
+		None => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	};
+
+	let map = read_map(part, args.get(1));
+
+	let plan = AStar.search(&map).plan.unwrap_or_else(|| {
+		eprintln!("the amphipods can't be organized");
+		process::exit(1);
+	});
+	let frames = replay::frames(&map, &plan).expect("the plan of the solver can be replayed");
+
+	if let Err(error) = replay::play(&mut io::stdout().lock(), &frames, color, Duration::from_millis(delay)) {
+		eprintln!("failed to replay: {}", error);
+		process::exit(1);
+	}
+}
+
+/// Generate random puzzles for day 23, with the energy they need and how many maps the solver
+/// expanded.
+fn generate(args: &[String]) {
+	let mut args = args.to_vec();
+	let seed = take_option(&mut args, "--seed", |_: &u64| true).unwrap_or_else(|| {
+		let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+		now.as_nanos() as u64
+	});
+	let count = take_option(&mut args, "--count", |count: &usize| *count > 0);
+
+	let number = |arg: Option<&String>| arg.and_then(|arg| arg.parse::<usize>().ok());
+	let (kinds, depth) = match (number(args.first()), number(args.get(1))) {
+		(Some(kinds), Some(depth)) => (kinds, depth),
+		_ => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	};
+	let directory = args.get(2).map(Path::new);
+	if count.is_some() && directory.is_none() {
+		eprintln!("--count needs a directory to write the burrows to\n\n{}", USAGE);
+		process::exit(2);
+	}
+
+	let mut rng = Rng::new(seed);
+	eprintln!("seed {}", seed);
+	if directory.is_some() {
+		println!("# burrow  energy  expanded");
+	}
+
+	for n in 1..=count.unwrap_or(1) {
+		let puzzle = generate::generate(kinds, depth, &mut rng).unwrap_or_else(|error| {
+			eprintln!("can't generate a burrow: {}", error);
+			process::exit(2);
+		});
+
+		match directory {
+			Some(directory) => {
+				let filename = format!("amphipods-{}x{}-{}-{}.txt", kinds, depth, seed, n);
+				if let Err(error) = fs::write(directory.join(&filename), puzzle.to_string()) {
+					eprintln!("failed to write {}: {}", filename, error);
+					process::exit(1);
+				}
+				println!("{}  {}  {}", filename, puzzle.energy, puzzle.expanded);
+			}
+			None => {
+				print!("{}", puzzle);
+				eprintln!("energy {}, expanded {} maps", puzzle.energy, puzzle.expanded);
+			}
+		}
+	}
+}
+
+/// Count and list the cheapest plans for day 23, or list the `k` cheapest plans.
+fn solutions(args: &[String]) {
+	let mut args = args.to_vec();
+	let cap = take_option(&mut args, "--cap", |cap: &usize| *cap > 0);
+	let best = take_option(&mut args, "--best", |best: &usize| *best > 0);
+	if cap.is_some() && best.is_some() {
+		eprintln!("--cap and --best can't be used together\n\n{}", USAGE);
+		process::exit(2);
+	}
+
+	let part: Part = match args.first() {
+		Some(part) => part.parse().unwrap_or_else(|error| {
+			eprintln!("{}\n\n{}", error, USAGE);
+			process::exit(2);
+		}),
+		None => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	};
+
+	let map = read_map(part, args.get(1));
+
+	let solutions = Solutions::new(&map);
+	let energy = solutions.energy().unwrap_or_else(|| {
+		println!("the amphipods can't be organized");
+		process::exit(1);
+	});
+
+	let plans = match best {
+		Some(k) => {
+			let plans = solutions.best(k);
+			let slack = plans.last().map_or(0, |plan| plan.energy - energy);
+			println!("the {} cheapest plans need {} to {} energy", plans.len(), energy, energy + slack);
+			plans
+		}
+		None => {
+			let count = solutions.count();
+			match count {
+				u64::MAX => println!("at least {} plans need {} energy", count, energy),
+				1 => println!("a single plan needs {} energy", energy),
+				_ => println!("{} plans need {} energy", count, energy),
+			}
+			solutions.cheapest(cap.unwrap_or(10))
+		}
+	};
+
+	for (i, plan) in plans.iter().enumerate() {
+		println!("\nplan {}, {} energy:", i + 1, plan.energy);
+		for step in &plan.moves {
+			println!("{}", step);
+		}
+	}
+}
+
+/// The squares written out as `(x, y), (x, y)`.
+fn squares(positions: &[Position]) -> String {
+	positions
+		.iter()
+		.map(|position| format!("({}, {})", position.x, position.y))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// Print the map and the energy spent so far.
+fn show(game: &Game) {
+	println!("{}energy {} after {} moves", game.map(), game.energy(), game.moves().count());
+}
+
+/// Play day 23 by hand, reading the commands from stdin.
+fn play(args: &[String]) {
+	let (part, burrow) = match (args.first().map(String::as_str), args.get(1), args.get(2)) {
+		(Some("23"), Some(part), Some(burrow)) => (part, burrow),
+		(Some(day), Some(_), Some(_)) if day != "23" => {
+			eprintln!("only day 23 can be played\n\n{}", USAGE);
+			process::exit(2);
+		}
+		_ => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	};
+	let part: Part = part.parse().unwrap_or_else(|error| {
+		eprintln!("{}\n\n{}", error, USAGE);
+		process::exit(2);
+	});
+
+	let mut game = Game::new(read_map(part, Some(burrow)));
+	let mut picked: Option<Position> = None;
+
+	println!("{}\n", PLAY_HELP);
+	show(&game);
+
+	loop {
+		print!("> ");
+		io::stdout().flush().ok();
+		let mut line = String::new();
+		if matches!(io::stdin().read_line(&mut line), Ok(0) | Err(_)) {
+			println!();
+			break;
+		}
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		match line.parse() {
+			Err(error) => println!("{}", error),
+			Ok(Command::Quit) => break,
+			Ok(Command::Help) => println!("{}", PLAY_HELP),
+			Ok(Command::Undo) => {
+				picked = None;
+				match game.undo() {
+					Some(step) => {
+						println!("took back {}", step);
+						show(&game);
+					}
+					None => println!("there is nothing to undo"),
+				}
+			}
+			Ok(Command::Redo) => {
+				picked = None;
+				match game.redo() {
+					Some(step) => {
+						println!("made {} again", step);
+						show(&game);
+					}
+					None => println!("there is nothing to redo"),
+				}
+			}
+			Ok(Command::Hint) => match game.hint() {
+				Some(plan) => {
+					let total = game.energy() + plan.energy;
+					print!("the cheapest way to finish needs {} more energy, {} in total", plan.energy, total);
+					match plan.moves.first() {
+						Some(step) => println!(", starting with {}", step),
+						None => println!(),
+					}
+				}
+				None => println!("the amphipods can't be organized from here, undo some moves"),
+			},
+			// A picked amphipod moves to an empty square, any other square picks the amphipod on it.
+			Ok(Command::Square(square)) => match picked.take() {
+				Some(from) if game.map().occupant(&square).is_none() => match game.play(from, square) {
+					Ok(step) => {
+						println!("moved {}", step);
+						show(&game);
+						if game.is_won() {
+							println!("the amphipods are organized with {} energy", game.energy());
+						}
+					}
+					Err(error) => {
+						println!("{}", error);
+						picked = Some(from);
+					}
+				},
+				_ => match game.targets(square) {
+					Ok((amphipod, targets)) if targets.is_empty() => {
+						println!("{} at ({}, {}) can't move now", amphipod.color, square.x, square.y)
+					}
+					Ok((amphipod, targets)) => {
+						let targets = squares(&targets);
+						println!("{} at ({}, {}) can move to {}", amphipod.color, square.x, square.y, targets);
+						picked = Some(square);
+					}
+					Err(error) => println!("{}", error),
+				},
+			},
+		}
+	}
+}
+
+/// Solve a part of a day, printing the answer.
+fn solve(args: &[String]) {
+	let (day, part) = match (args.first(), args.get(1)) {
+		(Some(day), Some(part)) => (day, part),
+		_ => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	};
+
+	let day: u8 = day.parse().unwrap_or_else(|_| {
+		eprintln!("invalid day {:?}\n\n{}", day, USAGE);
+		process::exit(2);
+	});
+	let part: Part = part.parse().unwrap_or_else(|error| {
+		eprintln!("{}\n\n{}", error, USAGE);
+		process::exit(2);
+	});
+
+	let input = read_input(args.get(2)).unwrap_or_else(|error| {
+		eprintln!("failed to read input: {}", error);
+		process::exit(1);
+	});
+
+	match days::run(day, part, &input) {
+		Ok(answer) => println!("{}", answer),
+		Err(error) => {
+			eprintln!("{}", error);
+			process::exit(1);
+		}
+	}
+}
+
+fn main() {
+	let args: Vec<String> = std::env::args().collect();
+
+	match args.get(1).map(String::as_str) {
+		Some("check") => check(&args[2..]),
+		Some("search") => search(&args[2..]),
+		Some("play") => play(&args[2..]),
+		Some("replay") => replay(&args[2..]),
+		Some("generate") => generate(&args[2..]),
+		Some("solutions") => solutions(&args[2..]),
+		_ => solve(&args[1..]),
+	}
+}
@@ -0,0 +1,66 @@
+use std::error::Error;
+
+use crate::input;
+use crate::sliding_window;
+use crate::Solution;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+	type Input = Vec<i32>;
+	type Output = i32;
+
+	fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+		Ok(input::lines(input)?)
+	}
+
+	fn part_a(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		Ok(sliding_window::increases(input, 1))
+	}
+
+	fn part_b(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		// The only difference to part a is the size of the sliding window.
+		Ok(sliding_window::increases(input, 3))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_example_input_should_result_in_7() {
+		// Given
+		let input = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+		// When
+		let result = Day01::part_a(&input).unwrap();
+
+		// Then
+		assert_eq!(7, result);
+	}
+
+	#[test]
+	fn test_example_input_should_result_in_5() {
+		// Given
+		let input = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+		// When
+		let result = Day01::part_b(&input).unwrap();
+
+		// Then
+		assert_eq!(5, result);
+	}
+
+	#[test]
+	fn test_corrupted_input_should_be_an_error() {
+		// Given
+		let input = "199\n200\n2o8\n210\n";
+
+		// When
+		let result = Day01::parse(input);
+
+		// Then
+		assert!(result.is_err());
+	}
+}
@@ -0,0 +1,113 @@
+use std::error::Error;
+
+use crate::submarine::{self, Instruction};
+use crate::Solution;
+
+#[derive(Debug, Clone)]
+struct Position {
+	horizontal: i32,
+	depth: i32,
+}
+
+#[derive(Debug, Clone)]
+struct State {
+	horizontal: i32,
+	depth: i32,
+	aim: i32,
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+	type Input = Vec<Instruction>;
+	type Output = i32;
+
+	fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+		Ok(submarine::parse_course(input)?)
+	}
+
+	fn part_a(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		let position = input.iter().fold(
+			Position {
+				horizontal: 0,
+				depth: 0,
+			},
+			|acc, i| match i {
+				Instruction::Forward(value) => Position {
+					horizontal: acc.horizontal + (*value as i32),
+					depth: acc.depth,
+				},
+				Instruction::Down(value) => Position {
+					horizontal: acc.horizontal,
+					depth: acc.depth + (*value as i32),
+				},
+				Instruction::Up(value) => Position {
+					horizontal: acc.horizontal,
+					depth: acc.depth - (*value as i32),
+				},
+			},
+		);
+
+		Ok(position.horizontal * position.depth)
+	}
+
+	fn part_b(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		let state = input.iter().fold(
+			State {
+				horizontal: 0,
+				depth: 0,
+				aim: 0,
+			},
+			|acc, i| match i {
+				Instruction::Forward(value) => State {
+					horizontal: acc.horizontal + (*value as i32),
+					depth: acc.depth + acc.aim * (*value as i32),
+					aim: acc.aim,
+				},
+				Instruction::Down(value) => State {
+					horizontal: acc.horizontal,
+					depth: acc.depth,
+					aim: acc.aim + (*value as i32),
+				},
+				Instruction::Up(value) => State {
+					horizontal: acc.horizontal,
+					depth: acc.depth,
+					aim: acc.aim - (*value as i32),
+				},
+			},
+		);
+
+		Ok(state.horizontal * state.depth)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
+	#[test]
+	fn test_example_input_should_result_in_150() {
+		// Given
+		let input = Day02::parse(EXAMPLE).unwrap();
+
+		// When
+		let result = Day02::part_a(&input).unwrap();
+
+		// Then
+		assert_eq!(150, result);
+	}
+
+	#[test]
+	fn test_example_input_should_result_in_900() {
+		// Given
+		let input = Day02::parse(EXAMPLE).unwrap();
+
+		// When
+		let result = Day02::part_b(&input).unwrap();
+
+		// Then
+		assert_eq!(900, result);
+	}
+}
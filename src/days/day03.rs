@@ -0,0 +1,123 @@
+use std::error::Error;
+
+use crate::input;
+use crate::Solution;
+
+/// A single bit of a word in the diagnostic report.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Bit {
+	Zero,
+	One,
+}
+
+impl TryFrom<char> for Bit {
+	type Error = String;
+
+	fn try_from(c: char) -> Result<Self, Self::Error> {
+		match c {
+			'0' => Ok(Bit::Zero),
+			'1' => Ok(Bit::One),
+			_ => Err(String::from("not a bit")),
+		}
+	}
+}
+
+/// The number a word stands for, most significant bit first.
+fn to_number(word: &[Bit]) -> usize {
+	word.iter().fold(0, |number, bit| number << 1 | (*bit == Bit::One) as usize)
+}
+
+fn gamma_filter(n: &usize, word_count: &usize) -> Bit {
+	match n > &(word_count / 2) {
+		true => Bit::One,
+		false => Bit::Zero,
+	}
+}
+
+fn sigma_filter(n: &usize, word_count: &usize) -> Bit {
+	match n < &(word_count / 2) {
+		true => Bit::One,
+		false => Bit::Zero,
+	}
+}
+
+/// Count the number of ones in each column of the report.
+fn column_sums(report: &[Vec<Bit>]) -> Vec<usize> {
+	let word_length = report.first().map_or(0, |word| word.len());
+
+	report.iter().fold(vec![0; word_length], |acc: Vec<usize>, e: &Vec<Bit>| {
+		e.iter()
+			.enumerate()
+			.map(|(i, bit)| match bit {
+				Bit::One => acc[i] + 1,
+				Bit::Zero => acc[i],
+			})
+			.collect()
+	})
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+	type Input = Vec<Vec<Bit>>;
+	type Output = usize;
+
+	/// Read the report, one word of bits per line, every word as long as the first.
+	fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+		let report = input::grid(input)?;
+		if report.first().is_none_or(|word: &Vec<Bit>| word.is_empty()) {
+			return Err("the report is empty".into());
+		}
+
+		Ok(report)
+	}
+
+	fn part_a(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		let word_count = input.len();
+		let sums = column_sums(input);
+
+		let gamma_rate: Vec<Bit> = sums.iter().map(|n| gamma_filter(n, &word_count)).collect();
+		let sigma_rate: Vec<Bit> = sums.iter().map(|n| sigma_filter(n, &word_count)).collect();
+
+		Ok(to_number(&gamma_rate) * to_number(&sigma_rate))
+	}
+
+	fn part_b(_: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		Err("part b of day 3 isn't solved yet".into())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const EXAMPLE: &str = "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+	#[test]
+	fn test_example_input_should_result_in_198() {
+		// Given
+		let input = Day03::parse(EXAMPLE).unwrap();
+
+		// When
+		let result = Day03::part_a(&input).unwrap();
+
+		// Then
+		assert_eq!(198, result);
+	}
+
+	#[test]
+	fn test_character_other_than_a_bit_should_be_an_error() {
+		assert!(Day03::parse("101\n1x1\n").is_err());
+	}
+
+	#[test]
+	fn test_ragged_report_should_be_an_error() {
+		assert!(Day03::parse("10\n1\n").is_err());
+	}
+
+	#[test]
+	fn test_empty_report_should_be_an_error() {
+		assert!(Day03::parse("").is_err());
+		assert!(Day03::parse("\n\n").is_err());
+	}
+}
@@ -0,0 +1,142 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::amphipod::{self, Burrow, Map, MapError};
+use crate::Solution;
+
+/// The rows that are folded away in the input, inserted between the two room rows for part b.
+const EXTRA_CAVE: &str = "  #D#C#B#A#
+  #D#B#A#C#";
+
+/// Insert the folded away rows into the burrow, keeping its energy costs.
+///
+/// Only the burrow from the puzzle, with rooms two squares deep, is folded. Returns an error for
+/// any other burrow, or if the amphipods don't match the ones in the folded away rows.
+pub fn unfold(map: &Map) -> Result<Map, MapError> {
+	if map.burrow.cells != Burrow::standard(2).cells {
+		return Err(MapError::NotFolded);
+	}
+
+	let mut lines: Vec<String> = map.to_string().lines().map(String::from).collect();
+	EXTRA_CAVE.lines().for_each(|l| lines.insert(lines.len() - 2, l.to_string()));
+
+	let costs: Vec<String> =
+		map.burrow.costs.iter().map(|(color, energy)| format!("{}={}", color, energy)).collect();
+	lines.push(String::new());
+	lines.push(costs.join(" "));
+
+	lines.join("\n").parse()
+}
+
+/// Why a part of the puzzle has no answer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolveError {
+	/// The amphipods can't be organized.
+	Unsolvable,
+	/// The burrow can't be unfolded for part b.
+	NotUnfolded(MapError),
+}
+
+impl fmt::Display for SolveError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			SolveError::Unsolvable => write!(f, "the amphipods can't be organized"),
+			SolveError::NotUnfolded(error) => write!(f, "can't unfold the burrow: {}", error),
+		}
+	}
+}
+
+impl Error for SolveError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			SolveError::NotUnfolded(error) => Some(error),
+			SolveError::Unsolvable => None,
+		}
+	}
+}
+
+/// The least energy needed to organize the amphipods in the map.
+fn solve(map: Map) -> Result<i32, SolveError> {
+	amphipod::easiest_moves(map).map(|plan| plan.energy).ok_or(SolveError::Unsolvable)
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+	type Input = Map;
+	type Output = i32;
+
+	fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+		Ok(input.parse()?)
+	}
+
+	fn part_a(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		Ok(solve(input.clone())?)
+	}
+
+	fn part_b(input: &Self::Input) -> Result<Self::Output, Box<dyn Error>> {
+		let map = unfold(input).map_err(SolveError::NotUnfolded)?;
+
+		Ok(solve(map)?)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::solution::{self, Part};
+
+	#[test]
+	fn test_unfolded_example_should_have_deeper_rooms() {
+		// Given
+		let input = Day23::parse("#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n").unwrap();
+
+		// When
+		let result = unfold(&input).unwrap().to_string();
+
+		// Then
+		assert_eq!(
+			"#############\n#...........#\n###B#C#B#D###\n  #D#C#B#A#\n  #D#B#A#C#\n  #A#D#C#A#\n  #########\n",
+			result
+		);
+	}
+
+	#[test]
+	fn test_unfolded_burrow_should_keep_its_energy_costs() {
+		// Given
+		let input = Day23::parse("#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n\nA=2\n").unwrap();
+
+		// When
+		let result = unfold(&input).unwrap();
+
+		// Then
+		assert_eq!((Some(2), Some(1000)), (result.burrow.energy_cost('A'), result.burrow.energy_cost('D')));
+	}
+
+	#[test]
+	fn test_other_burrow_should_not_be_unfolded() {
+		// Given
+		let input = Day23::parse("###############\n#.............#\n###B#C#B#D#E###\n  #A#D#C#A#E#\n  ###########\n\nE=1\n")
+			.unwrap();
+
+		// When
+		let result = (unfold(&input), Day23::part_b(&input).unwrap_err().downcast::<SolveError>());
+
+		// Then
+		assert_eq!(Err(MapError::NotFolded), result.0);
+		assert_eq!(SolveError::NotUnfolded(MapError::NotFolded), *result.1.unwrap());
+	}
+
+	#[test]
+	fn test_deadlocked_burrow_should_have_no_answer() {
+		// Given
+		let input = Day23::parse("#############\n#...D...A...#\n###.#B#C#.###\n  #A#B#C#D#\n  #########\n").unwrap();
+
+		// When
+		let result = solution::solve::<Day23>(Part::A, &input.to_string());
+
+		// Then
+		assert!(matches!(result, Err(solution::Error::NoAnswer(_))), "{:?}", result);
+		assert_eq!("no answer: the amphipods can't be organized", result.unwrap_err().to_string());
+	}
+}
@@ -0,0 +1,22 @@
+//! The solutions for each day, registered with the runner.
+
+use crate::solution::{self, Error, Part};
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day23;
+
+/// The days that have a solution.
+pub const DAYS: &[u8] = &[1, 2, 3, 23];
+
+/// Solve `part` of `day` with the given raw input.
+pub fn run(day: u8, part: Part, input: &str) -> Result<String, Error> {
+	match day {
+		1 => solution::solve::<day01::Day01>(part, input),
+		2 => solution::solve::<day02::Day02>(part, input),
+		3 => solution::solve::<day03::Day03>(part, input),
+		23 => solution::solve::<day23::Day23>(part, input),
+		_ => Err(Error::UnknownDay(day)),
+	}
+}
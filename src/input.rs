@@ -0,0 +1,367 @@
+//! Parsing of puzzle input.
+//!
+//! The parsers are strict by default, the first line that can't be parsed is reported as an
+//! error together with its line number. A lenient [`Parser`] can be used to skip those lines
+//! instead.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A line in the input that could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+	/// Line number, starting at 1.
+	pub line: usize,
+	/// Column number, starting at 1, if the error could be pinned to a single character.
+	pub column: Option<usize>,
+	/// The offending text.
+	pub text: String,
+	/// Why the text could not be parsed.
+	pub message: String,
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.column {
+			Some(column) => write!(f, "line {}, column {}: {}: {:?}", self.line, column, self.message, self.text),
+			None => write!(f, "line {}: {}: {:?}", self.line, self.message, self.text),
+		}
+	}
+}
+
+impl error::Error for ParseError {}
+
+/// Line oriented parser for puzzle input.
+///
+/// Trailing blank lines at the end of the input are always ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Parser {
+	lenient: bool,
+}
+
+impl Parser {
+	/// A parser that fails on the first line that can't be parsed.
+	pub fn strict() -> Self {
+		Parser { lenient: false }
+	}
+
+	/// A parser that silently skips every line that can't be parsed.
+	pub fn lenient() -> Self {
+		Parser { lenient: true }
+	}
+
+	/// Parse each line as a `T`.
+	///
+	/// ## Arguments
+	///
+	/// * `input` - the whole puzzle input
+	pub fn lines<T>(&self, input: &str) -> Result<Vec<T>, ParseError>
+	where
+		T: FromStr,
+		T::Err: fmt::Display,
+	{
+		self.parse_lines(numbered_lines(input))
+	}
+
+	/// Parse blocks of lines separated by blank lines, each line as a `T`.
+	///
+	/// Several blank lines in a row are treated as a single separator.
+	///
+	/// ## Arguments
+	///
+	/// * `input` - the whole puzzle input
+	pub fn blocks<T>(&self, input: &str) -> Result<Vec<Vec<T>>, ParseError>
+	where
+		T: FromStr,
+		T::Err: fmt::Display,
+	{
+		let mut blocks = vec![];
+		let mut block = vec![];
+
+		for (number, line) in numbered_lines(input) {
+			if line.trim().is_empty() {
+				if !block.is_empty() {
+					blocks.push(std::mem::take(&mut block));
+				}
+			} else {
+				block.push((number, line));
+			}
+		}
+		if !block.is_empty() {
+			blocks.push(block);
+		}
+
+		blocks.into_iter().map(|block| self.parse_lines(block)).collect()
+	}
+
+	/// Parse a rectangular grid, each character as a `T`.
+	///
+	/// All rows must be as wide as the first one. In lenient mode rows that are ragged or contain
+	/// a character that can't be parsed are skipped.
+	///
+	/// ## Arguments
+	///
+	/// * `input` - the whole puzzle input
+	pub fn grid<T>(&self, input: &str) -> Result<Vec<Vec<T>>, ParseError>
+	where
+		T: TryFrom<char>,
+		T::Error: fmt::Display,
+	{
+		let mut width = None;
+		let mut grid = vec![];
+
+		for (number, line) in numbered_lines(input) {
+			let row: Result<Vec<T>, ParseError> = line
+				.chars()
+				.enumerate()
+				.map(|(x, c)| {
+					T::try_from(c).map_err(|error| ParseError {
+						line: number,
+						column: Some(x + 1),
+						text: line.to_string(),
+						message: error.to_string(),
+					})
+				})
+				.collect();
+
+			let row = row.and_then(|row| match width {
+				Some(width) if width != row.len() => Err(ParseError {
+					line: number,
+					column: None,
+					text: line.to_string(),
+					message: format!("expected {} columns, found {}", width, row.len()),
+				}),
+				_ => Ok(row),
+			});
+
+			match row {
+				Ok(row) => {
+					width = Some(row.len());
+					grid.push(row);
+				}
+				Err(_) if self.lenient => continue,
+				Err(error) => return Err(error),
+			}
+		}
+
+		Ok(grid)
+	}
+
+	fn parse_lines<'a, T>(&self, lines: impl IntoIterator<Item = (usize, &'a str)>) -> Result<Vec<T>, ParseError>
+	where
+		T: FromStr,
+		T::Err: fmt::Display,
+	{
+		let parsed = lines.into_iter().map(|(number, line)| {
+			line.parse::<T>().map_err(|error| ParseError {
+				line: number,
+				column: None,
+				text: line.to_string(),
+				message: error.to_string(),
+			})
+		});
+
+		match self.lenient {
+			true => Ok(parsed.filter_map(Result::ok).collect()),
+			false => parsed.collect(),
+		}
+	}
+}
+
+/// Parse each line of the input as a `T`, failing on the first line that can't be parsed.
+pub fn lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+	T: FromStr,
+	T::Err: fmt::Display,
+{
+	Parser::strict().lines(input)
+}
+
+/// Parse blank line separated blocks of the input, failing on the first line that can't be
+/// parsed.
+pub fn blocks<T>(input: &str) -> Result<Vec<Vec<T>>, ParseError>
+where
+	T: FromStr,
+	T::Err: fmt::Display,
+{
+	Parser::strict().blocks(input)
+}
+
+/// Parse the input as a rectangular grid of `T`, failing on the first character that can't be
+/// parsed.
+pub fn grid<T>(input: &str) -> Result<Vec<Vec<T>>, ParseError>
+where
+	T: TryFrom<char>,
+	T::Error: fmt::Display,
+{
+	Parser::strict().grid(input)
+}
+
+/// Lines of the input paired with their line number, without the trailing blank lines.
+fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+	input.trim_end().lines().enumerate().map(|(i, line)| (i + 1, line))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	mod test_lines {
+		use super::*;
+
+		#[test]
+		fn valid_lines_should_be_parsed() {
+			// Given
+			let input = "199\n200\n208\n";
+
+			// When
+			let result = lines::<i32>(input);
+
+			// Then
+			assert_eq!(Ok(vec![199, 200, 208]), result);
+		}
+
+		#[test]
+		fn invalid_line_should_report_line_number_and_text() {
+			// Given
+			let input = "199\n2x0\n208\n";
+
+			// When
+			let result = lines::<i32>(input);
+
+			// Then
+			let error = result.unwrap_err();
+			assert_eq!(2, error.line);
+			assert_eq!("2x0", error.text);
+		}
+
+		#[test]
+		fn blank_line_in_the_middle_should_be_an_error() {
+			// Given
+			let input = "199\n\n208\n";
+
+			// When
+			let result = lines::<i32>(input);
+
+			// Then
+			assert_eq!(2, result.unwrap_err().line);
+		}
+
+		#[test]
+		fn trailing_blank_lines_should_be_ignored() {
+			// Given
+			let input = "199\n200\n\n\n";
+
+			// When
+			let result = lines::<i32>(input);
+
+			// Then
+			assert_eq!(Ok(vec![199, 200]), result);
+		}
+
+		#[test]
+		fn lenient_parser_should_skip_invalid_lines() {
+			// Given
+			let input = "199\n2x0\n208\n";
+
+			// When
+			let result = Parser::lenient().lines::<i32>(input);
+
+			// Then
+			assert_eq!(Ok(vec![199, 208]), result);
+		}
+	}
+
+	mod test_blocks {
+		use super::*;
+
+		#[test]
+		fn blocks_should_be_separated_by_blank_lines() {
+			// Given
+			let input = "1\n2\n\n3\n\n\n4\n5\n";
+
+			// When
+			let result = blocks::<i32>(input);
+
+			// Then
+			assert_eq!(Ok(vec![vec![1, 2], vec![3], vec![4, 5]]), result);
+		}
+
+		#[test]
+		fn invalid_line_should_report_line_number_in_whole_input() {
+			// Given
+			let input = "1\n2\n\n3\nx\n";
+
+			// When
+			let result = blocks::<i32>(input);
+
+			// Then
+			assert_eq!(5, result.unwrap_err().line);
+		}
+	}
+
+	mod test_grid {
+		use super::*;
+
+		#[derive(Debug, PartialEq)]
+		struct Digit(u32);
+
+		impl TryFrom<char> for Digit {
+			type Error = String;
+
+			fn try_from(c: char) -> Result<Self, Self::Error> {
+				c.to_digit(10).map(Digit).ok_or_else(|| String::from("not a digit"))
+			}
+		}
+
+		#[test]
+		fn rectangular_grid_should_be_parsed() {
+			// Given
+			let input = "12\n34\n";
+
+			// When
+			let result = grid::<Digit>(input);
+
+			// Then
+			assert_eq!(Ok(vec![vec![Digit(1), Digit(2)], vec![Digit(3), Digit(4)]]), result);
+		}
+
+		#[test]
+		fn invalid_character_should_report_line_and_column() {
+			// Given
+			let input = "12\n3x\n";
+
+			// When
+			let result = grid::<Digit>(input);
+
+			// Then
+			let error = result.unwrap_err();
+			assert_eq!((2, Some(2)), (error.line, error.column));
+		}
+
+		#[test]
+		fn ragged_row_should_be_an_error() {
+			// Given
+			let input = "12\n345\n";
+
+			// When
+			let result = grid::<Digit>(input);
+
+			// Then
+			assert_eq!(2, result.unwrap_err().line);
+		}
+
+		#[test]
+		fn lenient_parser_should_skip_invalid_rows() {
+			// Given
+			let input = "12\n3x\n56\n";
+
+			// When
+			let result = Parser::lenient().grid::<Digit>(input);
+
+			// Then
+			assert_eq!(Ok(vec![vec![Digit(1), Digit(2)], vec![Digit(5), Digit(6)]]), result);
+		}
+	}
+}
@@ -1,2 +1,8 @@
+pub mod amphipod;
+pub mod days;
+pub mod input;
 pub mod sliding_window;
-pub mod submarine;
\ No newline at end of file
+pub mod solution;
+pub mod submarine;
+
+pub use solution::Solution;
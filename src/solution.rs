@@ -0,0 +1,144 @@
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A solution to a single day of the calendar.
+///
+/// The input is parsed once and then handed to whichever part that should be solved, so the
+/// parsing only needs to be written once per day.
+pub trait Solution {
+	/// The parsed representation of the puzzle input.
+	type Input;
+	/// The answer to the puzzle, anything that can be printed.
+	type Output: fmt::Display;
+
+	/// Parse the raw puzzle input.
+	fn parse(input: &str) -> Result<Self::Input, Box<dyn error::Error>>;
+
+	/// Solve the first part of the puzzle, or fail if the input has no answer.
+	fn part_a(input: &Self::Input) -> Result<Self::Output, Box<dyn error::Error>>;
+
+	/// Solve the second part of the puzzle, or fail if the input has no answer.
+	fn part_b(input: &Self::Input) -> Result<Self::Output, Box<dyn error::Error>>;
+}
+
+/// Which part of a day that should be solved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Part {
+	A,
+	B,
+}
+
+impl FromStr for Part {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim() {
+			"a" | "A" => Ok(Part::A),
+			"b" | "B" => Ok(Part::B),
+			_ => Err(Error::InvalidPart(s.to_string())),
+		}
+	}
+}
+
+impl fmt::Display for Part {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Part::A => write!(f, "a"),
+			Part::B => write!(f, "b"),
+		}
+	}
+}
+
+/// Errors that can occur when running a solution.
+#[derive(Debug)]
+pub enum Error {
+	/// There is no solution registered for the day.
+	UnknownDay(u8),
+	/// The part was neither `a` nor `b`.
+	InvalidPart(String),
+	/// The input could not be parsed by the solution.
+	Parse(Box<dyn error::Error>),
+	/// The input was parsed but the part has no answer for it.
+	NoAnswer(Box<dyn error::Error>),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::UnknownDay(day) => write!(f, "no solution for day {}", day),
+			Error::InvalidPart(part) => write!(f, "invalid part {:?}, expected a or b", part),
+			Error::Parse(error) => write!(f, "failed to parse input: {}", error),
+			Error::NoAnswer(error) => write!(f, "no answer: {}", error),
+		}
+	}
+}
+
+impl error::Error for Error {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			Error::Parse(error) | Error::NoAnswer(error) => Some(error.as_ref()),
+			_ => None,
+		}
+	}
+}
+
+/// Parse `input` with `S` and solve `part` of it.
+///
+/// The answer is returned already formatted since different days have different kinds of
+/// answers.
+pub fn solve<S: Solution>(part: Part, input: &str) -> Result<String, Error> {
+	let parsed = S::parse(input).map_err(Error::Parse)?;
+
+	let answer = match part {
+		Part::A => S::part_a(&parsed),
+		Part::B => S::part_b(&parsed),
+	};
+
+	answer.map(|answer| answer.to_string()).map_err(Error::NoAnswer)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	mod test_part_fromstr {
+		use super::*;
+
+		#[test]
+		fn lowercase_letters_should_be_parts() {
+			// Given
+			let input = ["a", "b"];
+
+			// When
+			let result: Vec<Part> = input.iter().map(|s| s.parse().unwrap()).collect();
+
+			// Then
+			assert_eq!(vec![Part::A, Part::B], result);
+		}
+
+		#[test]
+		fn uppercase_letters_should_be_parts() {
+			// Given
+			let input = ["A", "B"];
+
+			// When
+			let result: Vec<Part> = input.iter().map(|s| s.parse().unwrap()).collect();
+
+			// Then
+			assert_eq!(vec![Part::A, Part::B], result);
+		}
+
+		#[test]
+		fn other_letters_should_be_invalid() {
+			// Given
+			let input = "c";
+
+			// When
+			let result = input.parse::<Part>();
+
+			// Then
+			assert!(matches!(result, Err(Error::InvalidPart(_))));
+		}
+	}
+}
@@ -1,3 +1,7 @@
+use std::error;
+use std::fmt;
+use std::num::ParseIntError;
+
 #[derive(Debug, PartialEq)]
 pub enum Instruction {
 	Forward(u32),
@@ -5,35 +9,181 @@ pub enum Instruction {
 	Up(u32),
 }
 
+/// Reasons an instruction could not be parsed.
+///
+/// The original input is kept so the error can be reported without having the input at hand.
 #[derive(Debug, Clone, PartialEq)]
-pub struct InstructionError;
-impl std::fmt::Display for InstructionError {
-	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		write!(f, "invalid instruction")
+pub enum InstructionError {
+	/// There was nothing but whitespace.
+	Empty,
+	/// The first word is not one of `forward`, `down` or `up`.
+	UnknownVerb { input: String, verb: String },
+	/// The verb was not followed by a number.
+	MissingArgument { input: String, verb: String },
+	/// There were more words after the number.
+	ExtraArguments { input: String, extra: Vec<String> },
+	/// The argument is not a valid unsigned number.
+	InvalidNumber {
+		input: String,
+		value: String,
+		source: ParseIntError,
+	},
+}
+
+impl InstructionError {
+	/// The input that failed to parse.
+	pub fn input(&self) -> &str {
+		match self {
+			InstructionError::Empty => "",
+			InstructionError::UnknownVerb { input, .. }
+			| InstructionError::MissingArgument { input, .. }
+			| InstructionError::ExtraArguments { input, .. }
+			| InstructionError::InvalidNumber { input, .. } => input,
+		}
+	}
+
+	/// The column, starting at 1, in the input where the problem is.
+	///
+	/// For a missing argument this is the column just after the input.
+	pub fn column(&self) -> usize {
+		let input = self.input();
+		let token_column = |n: usize| {
+			token_offsets(input)
+				.nth(n)
+				.map_or(input.chars().count() + 1, |offset| input[..offset].chars().count() + 1)
+		};
+
+		match self {
+			InstructionError::Empty | InstructionError::UnknownVerb { .. } => token_column(0),
+			InstructionError::MissingArgument { .. } => input.trim_end().chars().count() + 1,
+			InstructionError::InvalidNumber { .. } => token_column(1),
+			InstructionError::ExtraArguments { .. } => token_column(2),
+		}
 	}
 }
-impl std::convert::From<std::num::ParseIntError> for InstructionError {
-	fn from(_: std::num::ParseIntError) -> Self {
-		Self
+
+impl fmt::Display for InstructionError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			InstructionError::Empty => write!(f, "empty instruction"),
+			InstructionError::UnknownVerb { verb, .. } => {
+				write!(f, "unknown instruction {:?}, expected forward, down or up", verb)
+			}
+			InstructionError::MissingArgument { verb, .. } => write!(f, "missing argument to {:?}", verb),
+			InstructionError::ExtraArguments { extra, .. } => {
+				write!(f, "unexpected extra arguments {:?}", extra.join(" "))
+			}
+			InstructionError::InvalidNumber { value, source, .. } => {
+				write!(f, "invalid number {:?}: {}", value, source)
+			}
+		}
+	}
+}
+
+impl error::Error for InstructionError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			InstructionError::InvalidNumber { source, .. } => Some(source),
+			_ => None,
+		}
 	}
 }
 
 impl std::str::FromStr for Instruction {
-	// Err should probably be a combination of multiple errors
 	type Err = InstructionError;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let data: Vec<&str> = s.trim().split_whitespace().collect();
+		let data: Vec<&str> = s.split_whitespace().collect();
 
-		match data[..] {
-			["forward", value] => Ok(Instruction::Forward(value.parse::<u32>()?)),
-			["down", value] => Ok(Instruction::Down(value.parse::<u32>()?)),
-			["up", value] => Ok(Instruction::Up(value.parse::<u32>()?)),
-			_ => Err(InstructionError),
-		}
+		let (verb, arguments) = data.split_first().ok_or(InstructionError::Empty)?;
+
+		let instruction: fn(u32) -> Instruction = match *verb {
+			"forward" => Instruction::Forward,
+			"down" => Instruction::Down,
+			"up" => Instruction::Up,
+			_ => {
+				return Err(InstructionError::UnknownVerb {
+					input: s.to_string(),
+					verb: verb.to_string(),
+				})
+			}
+		};
+
+		let value = match arguments {
+			[] => {
+				return Err(InstructionError::MissingArgument {
+					input: s.to_string(),
+					verb: verb.to_string(),
+				})
+			}
+			[value] => value,
+			[_, extra @ ..] => {
+				return Err(InstructionError::ExtraArguments {
+					input: s.to_string(),
+					extra: extra.iter().map(|e| e.to_string()).collect(),
+				})
+			}
+		};
+
+		let value = value.parse::<u32>().map_err(|source| InstructionError::InvalidNumber {
+			input: s.to_string(),
+			value: value.to_string(),
+			source,
+		})?;
+
+		Ok(instruction(value))
+	}
+}
+
+/// Byte offsets of the start of each whitespace separated token.
+fn token_offsets(s: &str) -> impl Iterator<Item = usize> + '_ {
+	s.char_indices()
+		.zip(std::iter::once(' ').chain(s.chars()))
+		.filter(|((_, c), previous)| !c.is_whitespace() && previous.is_whitespace())
+		.map(|((offset, _), _)| offset)
+}
+
+/// An instruction in a course that could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseError {
+	/// Line number, starting at 1.
+	pub line: usize,
+	/// Column number, starting at 1.
+	pub column: usize,
+	pub error: InstructionError,
+}
+
+impl fmt::Display for CourseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "line {}, column {}: {}", self.line, self.column, self.error)
 	}
 }
 
+impl error::Error for CourseError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		Some(&self.error)
+	}
+}
+
+/// Parse a whole course, one instruction per line.
+///
+/// Trailing blank lines are ignored, any other line that isn't an instruction is reported
+/// together with where in the input it is.
+pub fn parse_course(input: &str) -> Result<Vec<Instruction>, CourseError> {
+	input
+		.trim_end()
+		.lines()
+		.enumerate()
+		.map(|(i, line)| {
+			line.parse::<Instruction>().map_err(|error| CourseError {
+				line: i + 1,
+				column: error.column(),
+				error,
+			})
+		})
+		.collect()
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -76,5 +226,145 @@ mod tests {
 			// Then
 			assert_eq!(Ok(Instruction::Up(100)), result);
 		}
+
+		#[test]
+		fn unknown_verb_should_be_reported() {
+			// Given
+			let input = String::from("backward 5");
+
+			// When
+			let result = input.parse::<Instruction>();
+
+			// Then
+			assert_eq!(
+				Err(InstructionError::UnknownVerb {
+					input: String::from("backward 5"),
+					verb: String::from("backward"),
+				}),
+				result
+			);
+		}
+
+		#[test]
+		fn missing_argument_should_be_reported() {
+			// Given
+			let input = String::from("down");
+
+			// When
+			let result = input.parse::<Instruction>();
+
+			// Then
+			assert_eq!(
+				Err(InstructionError::MissingArgument {
+					input: String::from("down"),
+					verb: String::from("down"),
+				}),
+				result
+			);
+		}
+
+		#[test]
+		fn extra_arguments_should_be_reported() {
+			// Given
+			let input = String::from("up 3 please");
+
+			// When
+			let result = input.parse::<Instruction>();
+
+			// Then
+			assert_eq!(
+				Err(InstructionError::ExtraArguments {
+					input: String::from("up 3 please"),
+					extra: vec![String::from("please")],
+				}),
+				result
+			);
+		}
+
+		#[test]
+		fn invalid_number_should_keep_the_source() {
+			// Given
+			let input = String::from("forward -5");
+
+			// When
+			let result = input.parse::<Instruction>();
+
+			// Then
+			let error = result.unwrap_err();
+			assert!(matches!(error, InstructionError::InvalidNumber { ref value, .. } if value == "-5"));
+			assert!(error::Error::source(&error).is_some());
+		}
+	}
+
+	mod test_column {
+		use super::*;
+
+		#[test]
+		fn unknown_verb_should_point_at_the_verb() {
+			// Given
+			let input = String::from("  sideways 5");
+
+			// When
+			let result = input.parse::<Instruction>().unwrap_err().column();
+
+			// Then
+			assert_eq!(3, result);
+		}
+
+		#[test]
+		fn invalid_number_should_point_at_the_number() {
+			// Given
+			let input = String::from("forward  x");
+
+			// When
+			let result = input.parse::<Instruction>().unwrap_err().column();
+
+			// Then
+			assert_eq!(10, result);
+		}
+
+		#[test]
+		fn missing_argument_should_point_after_the_verb() {
+			// Given
+			let input = String::from("up");
+
+			// When
+			let result = input.parse::<Instruction>().unwrap_err().column();
+
+			// Then
+			assert_eq!(3, result);
+		}
+	}
+
+	mod test_parse_course {
+		use super::*;
+
+		#[test]
+		fn valid_course_should_be_parsed() {
+			// Given
+			let input = "forward 5\ndown 5\nup 3\n";
+
+			// When
+			let result = parse_course(input);
+
+			// Then
+			assert_eq!(
+				Ok(vec![Instruction::Forward(5), Instruction::Down(5), Instruction::Up(3)]),
+				result
+			);
+		}
+
+		#[test]
+		fn invalid_instruction_should_report_line_and_column() {
+			// Given
+			let input = "forward 5\ndown five\nup 3\n";
+
+			// When
+			let result = parse_course(input);
+
+			// Then
+			let error = result.unwrap_err();
+			assert_eq!((2, 6), (error.line, error.column));
+		}
 	}
 }
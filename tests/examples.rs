@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::Path;
+
+use aoc2021::days;
+use aoc2021::solution::Part;
+
+/// Examples that take too long to solve in a debug build.
+///
+/// These are only run with `cargo test -- --ignored`.
+const SLOW: &[(u8, Part)] = &[(23, Part::B)];
+
+#[derive(Debug)]
+struct Example {
+	filename: String,
+	day: u8,
+	part_a: String,
+	part_b: String,
+}
+
+impl Example {
+	fn answers(&self) -> [(Part, &str); 2] {
+		[(Part::A, &self.part_a), (Part::B, &self.part_b)]
+	}
+}
+
+/// Read the examples and their expected answers from `examples/answers.txt`.
+fn manifest() -> Vec<Example> {
+	let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/answers.txt");
+	let manifest = fs::read_to_string(path).expect("failed to read manifest");
+
+	manifest
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(|line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+			[filename, day, part_a, part_b] => Example {
+				filename: filename.to_string(),
+				day: day.parse().expect("invalid day in manifest"),
+				part_a: part_a.to_string(),
+				part_b: part_b.to_string(),
+			},
+			_ => panic!("invalid line in manifest: {:?}", line),
+		})
+		.collect()
+}
+
+/// Solve every example part selected by `filter` and collect the ones with the wrong answer.
+fn check_examples(filter: impl Fn(u8, Part) -> bool) {
+	let mut failures = vec![];
+
+	for example in manifest() {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples").join(&example.filename);
+		let input = fs::read_to_string(&path).expect("failed to read example");
+
+		for (part, expected) in example.answers() {
+			if !filter(example.day, part) {
+				continue;
+			}
+
+			match days::run(example.day, part, &input) {
+				Ok(answer) if answer == expected => {}
+				Err(_) if expected == "-" => {}
+				result => failures.push(format!(
+					"{} day {} part {}: expected {}, got {:?}",
+					example.filename, example.day, part, expected, result
+				)),
+			}
+		}
+	}
+
+	assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_day_should_have_an_example() {
+	// Given
+	let examples = manifest();
+
+	// When
+	let missing: Vec<&u8> = days::DAYS
+		.iter()
+		.filter(|day| !examples.iter().any(|example| example.day == **day))
+		.collect();
+
+	// Then
+	assert!(missing.is_empty(), "days without examples: {:?}", missing);
+}
+
+#[test]
+fn examples_should_give_expected_answers() {
+	check_examples(|day, part| !SLOW.contains(&(day, part)));
+}
+
+#[test]
+#[ignore]
+fn slow_examples_should_give_expected_answers() {
+	check_examples(|day, part| SLOW.contains(&(day, part)));
+}